@@ -0,0 +1,61 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, Vec};
+
+/// Child contract deployed by the AutoShare factory for a single group.
+/// Payers send tokens straight to this contract's address; the factory later
+/// splits the held balance among the group's members through `payout`.
+#[contracttype]
+pub enum DataKey {
+    Factory,
+    GroupId,
+}
+
+/// Mirrors `MemberAmount` of the AutoShare factory.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemberAmount {
+    pub address: Address,
+    pub amount: i128,
+}
+
+#[contract]
+pub struct GroupSplitter;
+
+#[contractimpl]
+impl GroupSplitter {
+    /// Binds the splitter to the factory that deployed it and the group it serves.
+    pub fn __constructor(env: Env, factory: Address, group_id: BytesN<32>) {
+        env.storage().instance().set(&DataKey::Factory, &factory);
+        env.storage().instance().set(&DataKey::GroupId, &group_id);
+    }
+
+    /// Returns the factory contract that controls payouts.
+    pub fn factory(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Factory).unwrap()
+    }
+
+    /// Returns the id of the group this splitter belongs to.
+    pub fn group_id(env: Env) -> BytesN<32> {
+        env.storage().instance().get(&DataKey::GroupId).unwrap()
+    }
+
+    /// Transfers the given amounts of `token` out of the splitter. Only the factory can call.
+    pub fn payout(env: Env, token: Address, payouts: Vec<MemberAmount>) {
+        let factory: Address = env.storage().instance().get(&DataKey::Factory).unwrap();
+        factory.require_auth();
+
+        let client = token::TokenClient::new(&env, &token);
+        for payout in payouts.iter() {
+            if payout.amount > 0 {
+                client.transfer(
+                    &env.current_contract_address(),
+                    &payout.address,
+                    &payout.amount,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
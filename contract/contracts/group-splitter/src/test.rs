@@ -0,0 +1,81 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, token, vec, Address, BytesN, Env};
+
+fn setup(env: &Env) -> (Address, Address, Address) {
+    let factory = Address::generate(env);
+    let group_id = BytesN::from_array(env, &[7u8; 32]);
+    let splitter = env.register(GroupSplitter, (factory.clone(), group_id));
+
+    let token_admin = Address::generate(env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    token::StellarAssetClient::new(env, &token).mint(&splitter, &1000);
+
+    (factory, splitter, token)
+}
+
+#[test]
+fn test_constructor_stores_factory_and_group() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, splitter, _) = setup(&env);
+    let client = GroupSplitterClient::new(&env, &splitter);
+
+    assert_eq!(client.factory(), factory);
+    assert_eq!(client.group_id(), BytesN::from_array(&env, &[7u8; 32]));
+}
+
+#[test]
+fn test_factory_can_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (_, splitter, token) = setup(&env);
+    let client = GroupSplitterClient::new(&env, &splitter);
+
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.payout(
+        &token,
+        &vec![
+            &env,
+            MemberAmount {
+                address: member1.clone(),
+                amount: 600,
+            },
+            MemberAmount {
+                address: member2.clone(),
+                amount: 400,
+            },
+        ],
+    );
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&member1), 600);
+    assert_eq!(token_client.balance(&member2), 400);
+    assert_eq!(token_client.balance(&splitter), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_payout_requires_factory_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (_, splitter, token) = setup(&env);
+    // Stop mocking so the factory's authorization is actually required
+    env.set_auths(&[]);
+    let client = GroupSplitterClient::new(&env, &splitter);
+
+    client.payout(
+        &token,
+        &vec![
+            &env,
+            MemberAmount {
+                address: Address::generate(&env),
+                amount: 1000,
+            },
+        ],
+    );
+}
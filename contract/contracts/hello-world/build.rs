@@ -0,0 +1,15 @@
+//! Enables the tests that deploy the splitter from its built WASM once that
+//! WASM exists. Build it with `make build` or
+//! `cargo build --target wasm32v1-none --release -p group-splitter`.
+
+use std::path::Path;
+
+const SPLITTER_WASM: &str = "../../target/wasm32v1-none/release/group_splitter.wasm";
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(splitter_wasm)");
+    println!("cargo::rerun-if-changed={SPLITTER_WASM}");
+    if Path::new(SPLITTER_WASM).exists() {
+        println!("cargo::rustc-cfg=splitter_wasm");
+    }
+}
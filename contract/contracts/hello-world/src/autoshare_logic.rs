@@ -1,32 +1,103 @@
 use crate::base::errors::Error;
 use crate::base::events::{
-    AdminTransferred, AutoshareCreated, AutoshareUpdated, ContractPaused, ContractUnpaused,
-    Distribution, GroupActivated, GroupDeactivated, GroupDeleted, Withdrawal,
+    ActionApproved, ActionExecuted, ActionProposed, ActionQueued, AdminTransferCancelled,
+    AdminTransferProposed, AdminTransferred, AutoshareCreated, AutoshareUpdated, Claimed,
+    ContractPaused, ContractUnpaused, CouncilDisabled, CouncilUpdated, Distribution,
+    GroupActivated, GroupDeactivated, GroupDeleted, QueuedActionCancelled, QueuedActionExecuted,
+    RoleGranted, RoleRevoked, SplitterDeployed, SubscriptionPurchased, UsagesRefunded, Withdrawal,
 };
 use crate::base::types::{
-    AutoShareDetails, DistributionHistory, GroupMember, MemberAmount, PaymentHistory,
+    AdminAction, AutoShareDetails, BatchItem, BatchSummary, Council, CouncilProposal,
+    DistributionHistory, DistributionHistoryPage, DistributionMode, FeeMode, GroupMember,
+    GroupPage, MemberAmount, PaymentHistory, PaymentHistoryPage, PendingAdmin, PricingTier,
+    QueuedAction, Role, TokenAmount, TokenDistribution, Tranche, UsageLot,
+};
+use crate::interfaces::splitter::GroupSplitterClient;
+use soroban_sdk::{
+    contracttype, panic_with_error, token, Address, BytesN, Env, IntoVal, Map, String, Symbol,
+    TryFromVal, Val, Vec,
 };
-use soroban_sdk::{contracttype, token, Address, BytesN, Env, String, Vec};
 
 #[contracttype]
 pub enum DataKey {
     AutoShare(BytesN<32>),
+    // Legacy vector of all group ids, moved into the numbered `GroupList::All`
+    // entries below on first write.
     AllGroups,
     Admin,
+    PendingAdmin,
+    RoleMembers(Role),
+    Council,
+    CouncilProposal(u32),
+    NextProposalId,
+    TimelockDelay,
+    QueuedAction(u32),
+    NextQueuedActionId,
+    FeeRevenue(Address),
+    TokenLiabilities(Address),
+    GroupUsageLots(BytesN<32>),
+    TokenUsageFee(Address),
+    PricingTiers,
+    SubscriptionPlan(Address, u64),
+    SubscriptionExpiry(BytesN<32>),
+    ProtocolFeeBps,
+    GroupFeeMode(BytesN<32>),
+    GroupTranches(BytesN<32>),
+    GroupReceived(BytesN<32>),
     SupportedTokens,
-    UsageFee,
+    // Legacy vector histories, moved into the numbered entries below on first
+    // write or by `migrate_history`.
     UserPaymentHistory(Address),
     GroupPaymentHistory(BytesN<32>),
     GroupDistributionHistory(BytesN<32>),
     MemberDistributionHistory(Address),
     GroupMembers(BytesN<32>),
     IsPaused,
+    GroupDistributionMode(BytesN<32>),
+    Claimable(Address, Address),
+    ClaimableTokens(Address),
+    SplitterWasmHash,
+    GroupSplitter(BytesN<32>),
+    MemberGroups(Address),
+    ParentGroups(BytesN<32>),
+    UserPayment(Address, u32),
+    UserPaymentCount(Address),
+    GroupPayment(BytesN<32>, u32),
+    GroupPaymentCount(BytesN<32>),
+    GroupDistribution(BytesN<32>, u32),
+    GroupDistributionCount(BytesN<32>),
+    MemberDistribution(Address, u32),
+    MemberDistributionCount(Address),
+    GroupListEntry(GroupList, u32),
+    GroupListCount(GroupList),
+    GroupListPosition(GroupList, BytesN<32>),
+}
+
+/// A list of group ids stored as one entry per id, numbered from 0, with the
+/// next number kept under a separate counter. Removing an id clears its entry,
+/// so the positions of the other ids never shift.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupList {
+    All,
+    Creator(Address),
 }
 
 const DAY_IN_LEDGERS: u32 = 17280;
 const PERSISTENT_BUMP_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS; // 1 week
 const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
 
+/// Member splits are expressed in basis points and must add up to this total (100%).
+pub const TOTAL_BASIS_POINTS: u32 = 10_000;
+/// Total used by groups stored before splits moved to basis points.
+const LEGACY_TOTAL_PERCENTAGE: u32 = 100;
+/// Largest number of entries returned by a single paginated query.
+pub const MAX_PAGE_SIZE: u32 = 50;
+/// Most levels of child groups that can sit below a group.
+pub const MAX_NESTING_DEPTH: u32 = 3;
+/// Largest number of distributions in a single `distribute_batch` call.
+pub const MAX_BATCH_SIZE: u32 = 50;
+
 fn bump_persistent<K: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &K) {
     if env.storage().persistent().has(key) {
         env.storage().persistent().extend_ttl(
@@ -36,6 +107,88 @@ fn bump_persistent<K: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, ke
         );
     }
 }
+
+// ============================================================================
+// Stored Group Layouts
+// ============================================================================
+
+/// A member as stored before splits moved to basis points and members could
+/// take fixed amounts or child groups. Its percentages sum to
+/// `LEGACY_TOTAL_PERCENTAGE`.
+#[contracttype]
+struct LegacyGroupMember {
+    address: Address,
+    percentage: u32,
+}
+
+/// `AutoShareDetails` with its members left undecoded, so groups stored with
+/// either member layout can be read.
+#[contracttype]
+struct StoredDetails {
+    id: BytesN<32>,
+    name: String,
+    creator: Address,
+    usage_count: u32,
+    total_usages_paid: u32,
+    members: Vec<Val>,
+    is_active: bool,
+}
+
+/// Reads the group stored under `key`, upgrading legacy members.
+fn read_details(env: &Env, key: &DataKey) -> Option<AutoShareDetails> {
+    let stored: StoredDetails = env.storage().persistent().get(key)?;
+    Some(AutoShareDetails {
+        id: stored.id,
+        name: stored.name,
+        creator: stored.creator,
+        usage_count: stored.usage_count,
+        total_usages_paid: stored.total_usages_paid,
+        members: decode_members(env, &stored.members),
+        is_active: stored.is_active,
+    })
+}
+
+/// Reads the member list stored under `key`, upgrading legacy members.
+fn read_group_members(env: &Env, key: &DataKey) -> Option<Vec<GroupMember>> {
+    let stored: Vec<Val> = env.storage().persistent().get(key)?;
+    Some(decode_members(env, &stored))
+}
+
+/// Whether the group stored under `key` still holds legacy members, which
+/// `migrate_member_splits` rescales.
+fn has_legacy_members(env: &Env, key: &DataKey) -> bool {
+    let stored: Option<StoredDetails> = env.storage().persistent().get(key);
+    stored.is_some_and(|stored| stored.members.iter().any(|val| is_legacy_member(env, &val)))
+}
+
+/// Legacy members have no `fixed_amount` field.
+fn is_legacy_member(env: &Env, val: &Val) -> bool {
+    let fields: Map<Symbol, Val> =
+        Map::try_from_val(env, val).unwrap_or_else(|_| panic_with_error!(env, Error::InvalidInput));
+    !fields.contains_key(Symbol::new(env, "fixed_amount"))
+}
+
+/// Decodes stored members. Legacy members are paid their percentage directly.
+fn decode_members(env: &Env, stored: &Vec<Val>) -> Vec<GroupMember> {
+    let mut members: Vec<GroupMember> = Vec::new(env);
+    for val in stored.iter() {
+        let member = if !is_legacy_member(env, &val) {
+            GroupMember::try_from_val(env, &val)
+        } else {
+            LegacyGroupMember::try_from_val(env, &val).map(|legacy| GroupMember {
+                address: legacy.address,
+                percentage: legacy.percentage,
+                fixed_amount: 0,
+                fixed_token: None,
+                group: None,
+            })
+        }
+        .unwrap_or_else(|_| panic_with_error!(env, Error::InvalidInput));
+        members.push_back(member);
+    }
+    members
+}
+
 pub fn create_autoshare(
     env: Env,
     id: BytesN<32>,
@@ -43,6 +196,7 @@ pub fn create_autoshare(
     creator: Address,
     usage_count: u32,
     payment_token: Address,
+    plan_duration: Option<u64>,
 ) -> Result<(), Error> {
     creator.require_auth();
 
@@ -59,8 +213,16 @@ pub fn create_autoshare(
         return Err(Error::AlreadyExists);
     }
 
-    // Validate usage count
-    if usage_count == 0 {
+    // The id of a deleted group stays taken while its splitter exists, so
+    // deposits sent to that splitter never reach another creator's group
+    let splitter_key = DataKey::GroupSplitter(id.clone());
+    if env.storage().persistent().has(&splitter_key) {
+        bump_persistent(&env, &splitter_key);
+        return Err(Error::AlreadyExists);
+    }
+
+    // Validate usage count; a group paid by time plan alone may start with none
+    if usage_count == 0 && plan_duration.is_none() {
         return Err(Error::InvalidUsageCount);
     }
 
@@ -69,13 +231,15 @@ pub fn create_autoshare(
         return Err(Error::UnsupportedToken);
     }
 
-    // Calculate total cost
-    let usage_fee = get_usage_fee(env.clone());
-    let total_cost = (usage_count as i128) * (usage_fee as i128);
-
-    // Transfer tokens from creator to contract
-    let token_client = token::Client::new(&env, &payment_token);
-    token_client.transfer(&creator, env.current_contract_address(), &total_cost);
+    // Charge the creator for the usages and plan
+    charge_purchase(
+        &env,
+        &id,
+        &creator,
+        &payment_token,
+        usage_count,
+        plan_duration,
+    )?;
 
     let details = AutoShareDetails {
         id: id.clone(),
@@ -91,16 +255,9 @@ pub fn create_autoshare(
     env.storage().persistent().set(&key, &details);
     bump_persistent(&env, &key);
 
-    // Add to all groups list
-    let all_groups_key = DataKey::AllGroups;
-    let mut all_groups: Vec<BytesN<32>> = env
-        .storage()
-        .persistent()
-        .get(&all_groups_key)
-        .unwrap_or(Vec::new(&env));
-    all_groups.push_back(id.clone());
-    env.storage().persistent().set(&all_groups_key, &all_groups);
-    bump_persistent(&env, &all_groups_key);
+    // Add to all groups list and index the group under its creator
+    add_to_group_list(&env, &GroupList::All, &id);
+    add_to_group_list(&env, &GroupList::Creator(creator.clone()), &id);
 
     // Initialize empty members list
     let members_key = DataKey::GroupMembers(id.clone());
@@ -108,14 +265,10 @@ pub fn create_autoshare(
     env.storage().persistent().set(&members_key, &empty_members);
     bump_persistent(&env, &members_key);
 
-    // Record payment history
-    record_payment(
-        env.clone(),
-        creator.clone(),
-        id.clone(),
-        usage_count,
-        total_cost,
-    );
+    // Deploy the group's splitter contract when a splitter WASM is configured
+    if let Some(wasm_hash) = read_splitter_wasm_hash(&env) {
+        deploy_splitter(&env, &id, wasm_hash);
+    }
 
     AutoshareCreated {
         creator: creator.clone(),
@@ -127,7 +280,7 @@ pub fn create_autoshare(
 
 pub fn get_autoshare(env: Env, id: BytesN<32>) -> Result<AutoShareDetails, Error> {
     let key = DataKey::AutoShare(id);
-    let result: Option<AutoShareDetails> = env.storage().persistent().get(&key);
+    let result: Option<AutoShareDetails> = read_details(&env, &key);
     if result.is_some() {
         bump_persistent(&env, &key);
     }
@@ -135,17 +288,77 @@ pub fn get_autoshare(env: Env, id: BytesN<32>) -> Result<AutoShareDetails, Error
 }
 
 pub fn get_all_groups(env: Env) -> Vec<AutoShareDetails> {
-    let all_groups_key = DataKey::AllGroups;
-    let group_ids: Vec<BytesN<32>> = env
-        .storage()
-        .persistent()
-        .get(&all_groups_key)
-        .unwrap_or(Vec::new(&env));
-    if !group_ids.is_empty() {
-        bump_persistent(&env, &all_groups_key);
+    let group_ids = read_group_list(&env, &GroupList::All);
+    load_groups(&env, &group_ids)
+}
+
+pub fn get_groups_by_creator(env: Env, creator: Address) -> Vec<AutoShareDetails> {
+    let group_ids = read_group_list(&env, &GroupList::Creator(creator));
+    load_groups(&env, &group_ids)
+}
+
+/// Returns the groups that list `address` as a member or in a tranche.
+pub fn get_groups_by_member(env: Env, address: Address) -> Vec<AutoShareDetails> {
+    let group_ids = read_group_index(&env, &DataKey::MemberGroups(address));
+    load_groups(&env, &group_ids)
+}
+
+/// Returns the groups in positions `start..start + limit` of the list of all
+/// groups. Positions of deleted groups are skipped, so a page can hold fewer
+/// than `limit` groups.
+pub fn get_groups_page(env: Env, start: u32, limit: u32) -> Result<GroupPage, Error> {
+    let (page_ids, next_cursor) = read_group_list_page(&env, &GroupList::All, start, limit)?;
+    Ok(GroupPage {
+        items: load_groups(&env, &page_ids),
+        next_cursor,
+    })
+}
+
+/// Returns the groups in positions `start..start + limit` of the creator's
+/// groups, skipping deleted ones like `get_groups_page`.
+pub fn get_groups_by_creator_page(
+    env: Env,
+    creator: Address,
+    start: u32,
+    limit: u32,
+) -> Result<GroupPage, Error> {
+    let (page_ids, next_cursor) =
+        read_group_list_page(&env, &GroupList::Creator(creator), start, limit)?;
+    Ok(GroupPage {
+        items: load_groups(&env, &page_ids),
+        next_cursor,
+    })
+}
+
+/// Adds the groups in positions `start..start + limit` of the list of all groups to
+/// the lookup indexes. Admin only. Backfills groups created before the indexes
+/// existed; indexed groups are skipped. Returns the cursor of the next batch.
+pub fn reindex_groups(
+    env: Env,
+    admin: Address,
+    start: u32,
+    limit: u32,
+) -> Result<Option<u32>, Error> {
+    admin.require_auth();
+    require_admin_key(&env, &admin)?;
+
+    let (page_ids, next_cursor) = read_group_list_page(&env, &GroupList::All, start, limit)?;
+    for id in page_ids.iter() {
+        let details: AutoShareDetails = match read_details(&env, &DataKey::AutoShare(id.clone())) {
+            Some(details) => details,
+            None => continue,
+        };
+        add_to_group_list(&env, &GroupList::Creator(details.creator), &id);
+        let tranches = read_tranches(&env, &id);
+        for address in group_payees(&env, &details.members, &tranches).iter() {
+            add_to_group_index(&env, &DataKey::MemberGroups(address), &id);
+        }
     }
+    Ok(next_cursor)
+}
 
-    let mut result: Vec<AutoShareDetails> = Vec::new(&env);
+fn load_groups(env: &Env, group_ids: &Vec<BytesN<32>>) -> Vec<AutoShareDetails> {
+    let mut result: Vec<AutoShareDetails> = Vec::new(env);
     for id in group_ids.iter() {
         if let Ok(details) = get_autoshare(env.clone(), id) {
             result.push_back(details);
@@ -154,16 +367,244 @@ pub fn get_all_groups(env: Env) -> Vec<AutoShareDetails> {
     result
 }
 
-pub fn get_groups_by_creator(env: Env, creator: Address) -> Vec<AutoShareDetails> {
-    let all_groups = get_all_groups(env.clone());
-    let mut result: Vec<AutoShareDetails> = Vec::new(&env);
+// ============================================================================
+// Group Lists
+// ============================================================================
 
-    for group in all_groups.iter() {
-        if group.creator == creator {
-            result.push_back(group);
+impl GroupList {
+    fn count_key(&self) -> DataKey {
+        DataKey::GroupListCount(self.clone())
+    }
+
+    fn entry_key(&self, seq: u32) -> DataKey {
+        DataKey::GroupListEntry(self.clone(), seq)
+    }
+
+    fn position_key(&self, id: &BytesN<32>) -> DataKey {
+        DataKey::GroupListPosition(self.clone(), id.clone())
+    }
+
+    /// Returns the ids of a list still stored as a legacy vector.
+    fn read_legacy(&self, env: &Env) -> Vec<BytesN<32>> {
+        match self {
+            GroupList::All => read_group_index(env, &DataKey::AllGroups),
+            GroupList::Creator(_) => Vec::new(env),
         }
     }
-    result
+}
+
+fn read_group_list_count(env: &Env, list: &GroupList) -> Option<u32> {
+    let count_key = list.count_key();
+    let count: Option<u32> = env.storage().persistent().get(&count_key);
+    if count.is_some() {
+        bump_persistent(env, &count_key);
+    }
+    count
+}
+
+/// Returns the list's counter, first moving a legacy vector into numbered
+/// entries when the list has none yet.
+fn group_list_count(env: &Env, list: &GroupList) -> u32 {
+    if let Some(count) = read_group_list_count(env, list) {
+        return count;
+    }
+
+    let legacy = list.read_legacy(env);
+    let mut count = 0u32;
+    for id in legacy.iter() {
+        write_group_list_entry(env, list, count, &id);
+        count += 1;
+    }
+    if !legacy.is_empty() {
+        env.storage().persistent().remove(&DataKey::AllGroups);
+        let count_key = list.count_key();
+        env.storage().persistent().set(&count_key, &count);
+        bump_persistent(env, &count_key);
+    }
+    count
+}
+
+fn write_group_list_entry(env: &Env, list: &GroupList, seq: u32, id: &BytesN<32>) {
+    let entry_key = list.entry_key(seq);
+    env.storage().persistent().set(&entry_key, id);
+    bump_persistent(env, &entry_key);
+
+    let position_key = list.position_key(id);
+    env.storage().persistent().set(&position_key, &seq);
+    bump_persistent(env, &position_key);
+}
+
+/// Returns the position of `id` in a list of `count` entries, if it is listed.
+fn group_list_position(env: &Env, list: &GroupList, count: u32, id: &BytesN<32>) -> Option<u32> {
+    let seq: u32 = env.storage().persistent().get(&list.position_key(id))?;
+    let listed: Option<BytesN<32>> = env.storage().persistent().get(&list.entry_key(seq));
+    if seq < count && listed.as_ref() == Some(id) {
+        Some(seq)
+    } else {
+        None
+    }
+}
+
+/// Appends `id` to the list unless it is already listed.
+fn add_to_group_list(env: &Env, list: &GroupList, id: &BytesN<32>) {
+    let count = group_list_count(env, list);
+    if group_list_position(env, list, count, id).is_some() {
+        return;
+    }
+    write_group_list_entry(env, list, count, id);
+
+    let count_key = list.count_key();
+    env.storage().persistent().set(&count_key, &(count + 1));
+    bump_persistent(env, &count_key);
+}
+
+fn remove_from_group_list(env: &Env, list: &GroupList, id: &BytesN<32>) {
+    let count = group_list_count(env, list);
+    if let Some(seq) = group_list_position(env, list, count, id) {
+        env.storage().persistent().remove(&list.entry_key(seq));
+        env.storage().persistent().remove(&list.position_key(id));
+    }
+}
+
+/// Reads the ids in positions `start..end`. Lists that have not been migrated
+/// yet are served from their legacy vector.
+fn read_group_list_range(env: &Env, list: &GroupList, start: u32, end: u32) -> Vec<BytesN<32>> {
+    if read_group_list_count(env, list).is_none() {
+        let legacy = list.read_legacy(env);
+        return legacy.slice(start.min(legacy.len())..end.min(legacy.len()));
+    }
+    let mut ids = Vec::new(env);
+    for seq in start..end {
+        let entry_key = list.entry_key(seq);
+        if let Some(id) = env.storage().persistent().get::<_, BytesN<32>>(&entry_key) {
+            bump_persistent(env, &entry_key);
+            ids.push_back(id);
+        }
+    }
+    ids
+}
+
+fn group_list_len(env: &Env, list: &GroupList) -> u32 {
+    match read_group_list_count(env, list) {
+        Some(count) => count,
+        None => list.read_legacy(env).len(),
+    }
+}
+
+fn read_group_list(env: &Env, list: &GroupList) -> Vec<BytesN<32>> {
+    let len = group_list_len(env, list);
+    read_group_list_range(env, list, 0, len)
+}
+
+fn read_group_list_page(
+    env: &Env,
+    list: &GroupList,
+    start: u32,
+    limit: u32,
+) -> Result<(Vec<BytesN<32>>, Option<u32>), Error> {
+    let len = group_list_len(env, list);
+    let (start, end, next_cursor) = page_bounds(len, start, limit)?;
+    Ok((read_group_list_range(env, list, start, end), next_cursor))
+}
+
+fn read_group_index(env: &Env, key: &DataKey) -> Vec<BytesN<32>> {
+    let result: Option<Vec<BytesN<32>>> = env.storage().persistent().get(key);
+    if result.is_some() {
+        bump_persistent(env, key);
+    }
+    result.unwrap_or(Vec::new(env))
+}
+
+fn add_to_group_index(env: &Env, key: &DataKey, id: &BytesN<32>) {
+    let mut group_ids = read_group_index(env, key);
+    if !group_ids.contains(id) {
+        group_ids.push_back(id.clone());
+        env.storage().persistent().set(key, &group_ids);
+        bump_persistent(env, key);
+    }
+}
+
+/// Returns every address the group can pay: its members and the members of
+/// each tranche.
+fn group_payees(env: &Env, members: &Vec<GroupMember>, tranches: &Vec<Tranche>) -> Vec<Address> {
+    let mut payees: Vec<Address> = Vec::new(env);
+    for member in members.iter() {
+        if !payees.contains(&member.address) {
+            payees.push_back(member.address);
+        }
+    }
+    for tranche in tranches.iter() {
+        for member in tranche.members.iter() {
+            if !payees.contains(&member.address) {
+                payees.push_back(member.address);
+            }
+        }
+    }
+    payees
+}
+
+/// Keeps the member index in sync when the group's payees change from `old`
+/// to `new`.
+fn update_member_index(env: &Env, id: &BytesN<32>, old: &Vec<Address>, new: &Vec<Address>) {
+    for address in old.iter() {
+        if !new.contains(&address) {
+            remove_from_group_index(env, &DataKey::MemberGroups(address), id);
+        }
+    }
+    for address in new.iter() {
+        add_to_group_index(env, &DataKey::MemberGroups(address), id);
+    }
+}
+
+/// Returns the child groups that `members` pass shares down to.
+fn child_group_ids(env: &Env, members: &Vec<GroupMember>) -> Vec<BytesN<32>> {
+    let mut children: Vec<BytesN<32>> = Vec::new(env);
+    for member in members.iter() {
+        if let Some(child) = member.group {
+            if !children.contains(&child) {
+                children.push_back(child);
+            }
+        }
+    }
+    children
+}
+
+/// Keeps each child group's list of parents in sync when group `id` changes
+/// its child groups from `old` to `new`.
+fn update_parent_index(env: &Env, id: &BytesN<32>, old: &Vec<BytesN<32>>, new: &Vec<BytesN<32>>) {
+    for child in old.iter() {
+        if !new.contains(&child) {
+            remove_from_group_index(env, &DataKey::ParentGroups(child), id);
+        }
+    }
+    for child in new.iter() {
+        add_to_group_index(env, &DataKey::ParentGroups(child), id);
+    }
+}
+
+fn remove_from_group_index(env: &Env, key: &DataKey, id: &BytesN<32>) {
+    let mut group_ids = read_group_index(env, key);
+    if let Some(idx) = group_ids.first_index_of(id) {
+        group_ids.remove(idx);
+        if group_ids.is_empty() {
+            env.storage().persistent().remove(key);
+        } else {
+            env.storage().persistent().set(key, &group_ids);
+            bump_persistent(env, key);
+        }
+    }
+}
+
+/// Clamps a page request to a list of `len` entries, returning the `start..end`
+/// range and the cursor of the following page.
+fn page_bounds(len: u32, start: u32, limit: u32) -> Result<(u32, u32, Option<u32>), Error> {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(Error::InvalidPageSize);
+    }
+    let start = start.min(len);
+    let end = start.saturating_add(limit).min(len);
+    let next_cursor = if end < len { Some(end) } else { None };
+    Ok((start, end, next_cursor))
 }
 
 pub fn is_group_member(env: Env, id: BytesN<32>, address: Address) -> Result<bool, Error> {
@@ -175,11 +616,8 @@ pub fn is_group_member(env: Env, id: BytesN<32>, address: Address) -> Result<boo
     bump_persistent(&env, &group_key);
 
     let members_key = DataKey::GroupMembers(id);
-    let members: Vec<GroupMember> = env
-        .storage()
-        .persistent()
-        .get(&members_key)
-        .unwrap_or(Vec::new(&env));
+    let members: Vec<GroupMember> =
+        read_group_members(&env, &members_key).unwrap_or(Vec::new(&env));
     if !members.is_empty() {
         bump_persistent(&env, &members_key);
     }
@@ -212,11 +650,7 @@ pub fn add_group_member(
     }
 
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
     bump_persistent(&env, &key);
 
     // Only the group creator can add members
@@ -239,6 +673,9 @@ pub fn add_group_member(
     details.members.push_back(GroupMember {
         address: address.clone(),
         percentage,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     // Validate total percentage after adding
@@ -250,21 +687,23 @@ pub fn add_group_member(
 
     // Also update the GroupMembers storage to keep both places in sync
     let members_key = DataKey::GroupMembers(id.clone());
-    let mut members: Vec<GroupMember> = env
-        .storage()
-        .persistent()
-        .get(&members_key)
-        .unwrap_or(Vec::new(&env));
+    let mut members: Vec<GroupMember> =
+        read_group_members(&env, &members_key).unwrap_or(Vec::new(&env));
     if !members.is_empty() {
         bump_persistent(&env, &members_key);
     }
     members.push_back(GroupMember {
         address: address.clone(),
         percentage,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     env.storage().persistent().set(&members_key, &members);
     bump_persistent(&env, &members_key);
 
+    add_to_group_index(&env, &DataKey::MemberGroups(address), &id);
+
     Ok(())
 }
 
@@ -281,11 +720,7 @@ pub fn remove_group_member(
     }
 
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
     bump_persistent(&env, &key);
 
     if details.creator != caller {
@@ -309,6 +744,21 @@ pub fn remove_group_member(
         return Err(Error::MemberNotFound);
     }
 
+    // A member who is still paid through a tranche stays in the index
+    let tranches = read_tranches(&env, &id);
+    update_member_index(
+        &env,
+        &id,
+        &group_payees(&env, &details.members, &tranches),
+        &group_payees(&env, &new_members, &tranches),
+    );
+    update_parent_index(
+        &env,
+        &id,
+        &child_group_ids(&env, &details.members),
+        &child_group_ids(&env, &new_members),
+    );
+
     details.members = new_members.clone();
     env.storage().persistent().set(&key, &details);
     bump_persistent(&env, &key);
@@ -329,31 +779,59 @@ pub fn remove_group_member(
 // Admin Management
 // ============================================================================
 
-pub fn initialize_admin(env: Env, admin: Address) {
+/// Stores the admin at deploy time and supports each token in `usage_fees` at
+/// its fee per usage. A token listed twice keeps its first fee.
+pub fn initialize(env: Env, admin: Address, usage_fees: Vec<TokenAmount>) {
+    let mut tokens: Vec<Address> = Vec::new(&env);
+    for usage_fee in usage_fees.iter() {
+        if usage_fee.amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        if !tokens.contains(&usage_fee.token) {
+            tokens.push_back(usage_fee.token.clone());
+            write_usage_fee(&env, usage_fee.token, usage_fee.amount);
+        }
+    }
+    write_initial_config(&env, &admin, &tokens);
+}
+
+/// Initializes the admin of a contract that has none. Contracts set up by the
+/// constructor always have one, so this returns `AlreadyInitialized`.
+pub fn initialize_admin(env: Env, admin: Address) -> Result<(), Error> {
     admin.require_auth();
-    let admin_key = DataKey::Admin;
 
-    // Only set if not already initialized
-    if !env.storage().persistent().has(&admin_key) {
-        env.storage().persistent().set(&admin_key, &admin);
+    let admin_key = DataKey::Admin;
+    if env.storage().persistent().has(&admin_key) {
         bump_persistent(&env, &admin_key);
+        return Err(Error::AlreadyInitialized);
+    }
 
-        // Initialize default usage fee (10 tokens per usage)
-        let usage_fee_key = DataKey::UsageFee;
-        env.storage().persistent().set(&usage_fee_key, &10u32);
-        bump_persistent(&env, &usage_fee_key);
+    write_initial_config(&env, &admin, &Vec::new(&env));
+    Ok(())
+}
 
-        // Initialize empty supported tokens list
-        let tokens_key = DataKey::SupportedTokens;
-        let empty_tokens: Vec<Address> = Vec::new(&env);
-        env.storage().persistent().set(&tokens_key, &empty_tokens);
-        bump_persistent(&env, &tokens_key);
-    } else {
-        bump_persistent(&env, &admin_key);
-    }
+fn write_initial_config(env: &Env, admin: &Address, tokens: &Vec<Address>) {
+    let admin_key = DataKey::Admin;
+    env.storage().persistent().set(&admin_key, admin);
+    bump_persistent(env, &admin_key);
+
+    let tokens_key = DataKey::SupportedTokens;
+    env.storage().persistent().set(&tokens_key, tokens);
+    bump_persistent(env, &tokens_key);
 }
 
+/// Passes if `caller` is the admin and no council is set. With a council,
+/// privileged changes go through council proposals instead.
 fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
+    if read_council(env).is_some() {
+        return Err(Error::CouncilModeActive);
+    }
+    require_admin_key(env, caller)
+}
+
+/// Passes if `caller` is the admin, whether or not a council is set. Used by
+/// data migrations, which do not change configuration or move funds.
+fn require_admin_key(env: &Env, caller: &Address) -> Result<(), Error> {
     let admin_key = DataKey::Admin;
     let admin: Address = env
         .storage()
@@ -378,58 +856,617 @@ pub fn get_admin(env: Env) -> Result<Address, Error> {
     result.ok_or(Error::NotFound)
 }
 
-pub fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) -> Result<(), Error> {
+/// Proposes `new_admin` as the next admin. The transfer only happens once
+/// `new_admin` accepts, before `expires_at` (a ledger timestamp) if given.
+/// Replaces any earlier proposal.
+pub fn propose_admin(
+    env: Env,
+    current_admin: Address,
+    new_admin: Address,
+    expires_at: Option<u64>,
+) -> Result<(), Error> {
     current_admin.require_auth();
     require_admin(&env, &current_admin)?;
 
+    apply_propose_admin(&env, current_admin, new_admin, expires_at)
+}
+
+fn apply_propose_admin(
+    env: &Env,
+    current_admin: Address,
+    new_admin: Address,
+    expires_at: Option<u64>,
+) -> Result<(), Error> {
+    if let Some(expires_at) = expires_at {
+        if expires_at <= env.ledger().timestamp() {
+            return Err(Error::InvalidInput);
+        }
+    }
+
+    let pending_key = DataKey::PendingAdmin;
+    env.storage().persistent().set(
+        &pending_key,
+        &PendingAdmin {
+            new_admin: new_admin.clone(),
+            expires_at,
+        },
+    );
+    bump_persistent(env, &pending_key);
+
+    AdminTransferProposed {
+        current_admin,
+        new_admin,
+        expires_at,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Completes a pending admin transfer. Must be called by the proposed admin.
+pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+    new_admin.require_auth();
+
+    let pending = read_pending_admin(&env).ok_or(Error::NoPendingAdmin)?;
+    if pending.new_admin != new_admin {
+        return Err(Error::Unauthorized);
+    }
+    if let Some(expires_at) = pending.expires_at {
+        if env.ledger().timestamp() > expires_at {
+            return Err(Error::AdminProposalExpired);
+        }
+    }
+
+    let old_admin = get_admin(env.clone())?;
     let admin_key = DataKey::Admin;
     env.storage().persistent().set(&admin_key, &new_admin);
     bump_persistent(&env, &admin_key);
+    env.storage().persistent().remove(&DataKey::PendingAdmin);
+
     AdminTransferred {
-        old_admin: current_admin,
+        old_admin,
         new_admin,
     }
     .publish(&env);
     Ok(())
 }
 
+/// Withdraws a pending admin transfer. Only the current admin can cancel.
+pub fn cancel_admin_transfer(env: Env, admin: Address) -> Result<(), Error> {
+    admin.require_auth();
+    require_admin(&env, &admin)?;
+
+    apply_cancel_admin_transfer(&env, admin)
+}
+
+fn apply_cancel_admin_transfer(env: &Env, admin: Address) -> Result<(), Error> {
+    let pending = read_pending_admin(env).ok_or(Error::NoPendingAdmin)?;
+    env.storage().persistent().remove(&DataKey::PendingAdmin);
+
+    AdminTransferCancelled {
+        admin,
+        new_admin: pending.new_admin,
+    }
+    .publish(env);
+    Ok(())
+}
+
+pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+    read_pending_admin(&env)
+}
+
+fn read_pending_admin(env: &Env) -> Option<PendingAdmin> {
+    let pending_key = DataKey::PendingAdmin;
+    let result: Option<PendingAdmin> = env.storage().persistent().get(&pending_key);
+    if result.is_some() {
+        bump_persistent(env, &pending_key);
+    }
+    result
+}
+
 // ============================================================================
-// Pause Management
+// Roles
 // ============================================================================
 
-pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
+/// Passes if `caller` holds `role` or passes `require_admin`. Like the admin,
+/// role holders cannot act while a council is set.
+fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+    if read_council(env).is_some() {
+        return Err(Error::CouncilModeActive);
+    }
+    if read_role_members(env, role).contains(caller) {
+        return Ok(());
+    }
+    require_admin(env, caller)
+}
+
+fn read_role_members(env: &Env, role: Role) -> Vec<Address> {
+    let role_key = DataKey::RoleMembers(role);
+    let result: Option<Vec<Address>> = env.storage().persistent().get(&role_key);
+    if result.is_some() {
+        bump_persistent(env, &role_key);
+    }
+    result.unwrap_or(Vec::new(env))
+}
+
+pub fn grant_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
     admin.require_auth();
     require_admin(&env, &admin)?;
 
-    let pause_key = DataKey::IsPaused;
-    let is_paused: bool = env.storage().persistent().get(&pause_key).unwrap_or(false);
-    bump_persistent(&env, &pause_key);
+    apply_grant_role(&env, admin, role, account)
+}
 
-    if is_paused {
-        return Err(Error::AlreadyPaused);
+fn apply_grant_role(env: &Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
+    let mut members = read_role_members(env, role);
+    if members.contains(&account) {
+        return Err(Error::AlreadyExists);
     }
+    members.push_back(account.clone());
 
-    env.storage().persistent().set(&pause_key, &true);
-    bump_persistent(&env, &pause_key);
-    ContractPaused {}.publish(&env);
+    let role_key = DataKey::RoleMembers(role);
+    env.storage().persistent().set(&role_key, &members);
+    bump_persistent(env, &role_key);
+
+    RoleGranted {
+        role,
+        account,
+        admin,
+    }
+    .publish(env);
     Ok(())
 }
 
-pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
+pub fn revoke_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
     admin.require_auth();
     require_admin(&env, &admin)?;
 
-    let pause_key = DataKey::IsPaused;
-    let is_paused: bool = env.storage().persistent().get(&pause_key).unwrap_or(false);
-    bump_persistent(&env, &pause_key);
+    apply_revoke_role(&env, admin, role, account)
+}
 
-    if !is_paused {
-        return Err(Error::NotPaused);
+fn apply_revoke_role(env: &Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
+    let mut members = read_role_members(env, role);
+    let idx = members.first_index_of(&account).ok_or(Error::NotFound)?;
+    members.remove(idx);
+
+    let role_key = DataKey::RoleMembers(role);
+    if members.is_empty() {
+        env.storage().persistent().remove(&role_key);
+    } else {
+        env.storage().persistent().set(&role_key, &members);
+        bump_persistent(env, &role_key);
     }
 
-    env.storage().persistent().set(&pause_key, &false);
-    bump_persistent(&env, &pause_key);
-    ContractUnpaused {}.publish(&env);
+    RoleRevoked {
+        role,
+        account,
+        admin,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Returns whether `account` was granted `role`. The admin passes every role
+/// check without being listed.
+pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+    read_role_members(&env, role).contains(&account)
+}
+
+pub fn get_role_members(env: Env, role: Role) -> Vec<Address> {
+    read_role_members(&env, role)
+}
+
+// ============================================================================
+// Council
+// ============================================================================
+
+/// Hands privileged changes to a council of `signers`, `threshold` of whom must
+/// approve each action. Admin only; once set, the council changes itself.
+pub fn set_council(
+    env: Env,
+    admin: Address,
+    signers: Vec<Address>,
+    threshold: u32,
+) -> Result<(), Error> {
+    admin.require_auth();
+    require_admin(&env, &admin)?;
+    apply_set_council(&env, signers, threshold)
+}
+
+fn apply_set_council(env: &Env, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+    if threshold == 0 || threshold > signers.len() {
+        return Err(Error::InvalidInput);
+    }
+    for (i, signer) in signers.iter().enumerate() {
+        if signers.slice(i as u32 + 1..).contains(&signer) {
+            return Err(Error::DuplicateMember);
+        }
+    }
+
+    let council_key = DataKey::Council;
+    env.storage().persistent().set(
+        &council_key,
+        &Council {
+            signers: signers.clone(),
+            threshold,
+        },
+    );
+    bump_persistent(env, &council_key);
+
+    CouncilUpdated { signers, threshold }.publish(env);
+    Ok(())
+}
+
+fn apply_disable_council(env: &Env) -> Result<(), Error> {
+    env.storage().persistent().remove(&DataKey::Council);
+    CouncilDisabled {}.publish(env);
+    Ok(())
+}
+
+pub fn get_council(env: Env) -> Option<Council> {
+    read_council(&env)
+}
+
+fn read_council(env: &Env) -> Option<Council> {
+    let council_key = DataKey::Council;
+    let result: Option<Council> = env.storage().persistent().get(&council_key);
+    if result.is_some() {
+        bump_persistent(env, &council_key);
+    }
+    result
+}
+
+fn require_council_signer(env: &Env, signer: &Address) -> Result<Council, Error> {
+    let council = read_council(env).ok_or(Error::CouncilNotEnabled)?;
+    if !council.signers.contains(signer) {
+        return Err(Error::Unauthorized);
+    }
+    Ok(council)
+}
+
+/// Records `action` as a council proposal approved by `signer`, and executes it
+/// if that already meets the threshold. Returns the proposal id.
+pub fn propose_action(env: Env, signer: Address, action: AdminAction) -> Result<u32, Error> {
+    signer.require_auth();
+    let council = require_council_signer(&env, &signer)?;
+
+    let id_key = DataKey::NextProposalId;
+    let proposal_id: u32 = env.storage().persistent().get(&id_key).unwrap_or(0);
+    env.storage().persistent().set(&id_key, &(proposal_id + 1));
+    bump_persistent(&env, &id_key);
+
+    let mut approvals = Vec::new(&env);
+    approvals.push_back(signer.clone());
+    let proposal = CouncilProposal {
+        id: proposal_id,
+        action,
+        proposer: signer.clone(),
+        approvals,
+        executed: false,
+    };
+
+    ActionProposed {
+        proposal_id,
+        proposer: signer,
+    }
+    .publish(&env);
+
+    store_proposal(&env, &proposal);
+    execute_if_approved(&env, &council, proposal)?;
+    Ok(proposal_id)
+}
+
+/// Adds `signer`'s approval to a proposal and executes it once the threshold is
+/// reached. Returns whether the proposal has been executed.
+pub fn approve_action(env: Env, signer: Address, proposal_id: u32) -> Result<bool, Error> {
+    signer.require_auth();
+    let council = require_council_signer(&env, &signer)?;
+
+    let mut proposal = get_proposal(env.clone(), proposal_id)?;
+    if proposal.executed {
+        return Err(Error::ProposalAlreadyExecuted);
+    }
+    if proposal.approvals.contains(&signer) {
+        return Err(Error::AlreadyExists);
+    }
+    proposal.approvals.push_back(signer.clone());
+    store_proposal(&env, &proposal);
+
+    ActionApproved {
+        proposal_id,
+        signer,
+    }
+    .publish(&env);
+
+    execute_if_approved(&env, &council, proposal)
+}
+
+pub fn get_proposal(env: Env, proposal_id: u32) -> Result<CouncilProposal, Error> {
+    let proposal_key = DataKey::CouncilProposal(proposal_id);
+    let proposal: CouncilProposal = env
+        .storage()
+        .persistent()
+        .get(&proposal_key)
+        .ok_or(Error::NotFound)?;
+    bump_persistent(&env, &proposal_key);
+    Ok(proposal)
+}
+
+fn store_proposal(env: &Env, proposal: &CouncilProposal) {
+    let proposal_key = DataKey::CouncilProposal(proposal.id);
+    env.storage().persistent().set(&proposal_key, proposal);
+    bump_persistent(env, &proposal_key);
+}
+
+/// Executes the proposal if approvals from current signers meet the threshold.
+fn execute_if_approved(
+    env: &Env,
+    council: &Council,
+    mut proposal: CouncilProposal,
+) -> Result<bool, Error> {
+    let approvals = proposal
+        .approvals
+        .iter()
+        .filter(|approver| council.signers.contains(approver))
+        .count() as u32;
+    if approvals < council.threshold {
+        return Ok(false);
+    }
+
+    proposal.executed = true;
+    store_proposal(env, &proposal);
+    // Timelocked actions approved by the council still wait out the delay
+    if is_timelocked(&proposal.action) && read_timelock_delay(env) > 0 {
+        enqueue_action(env, proposal.action, env.current_contract_address());
+    } else {
+        execute_admin_action(env, proposal.action, &env.current_contract_address())?;
+    }
+
+    ActionExecuted {
+        proposal_id: proposal.id,
+    }
+    .publish(env);
+    Ok(true)
+}
+
+/// Applies an approved privileged action. `actor` is reported as the admin in
+/// the events the action emits.
+fn execute_admin_action(env: &Env, action: AdminAction, actor: &Address) -> Result<(), Error> {
+    match action {
+        AdminAction::Withdraw(token, amount, recipient) => {
+            apply_withdraw(env, token, amount, recipient)
+        }
+        AdminAction::SetUsageFee(token, fee) => apply_usage_fee(env, token, fee),
+        AdminAction::SetPricingTiers(tiers) => apply_pricing_tiers(env, tiers),
+        AdminAction::SetProtocolFee(fee_bps) => apply_protocol_fee(env, fee_bps),
+        AdminAction::SetSubscriptionPlan(token, duration, price) => {
+            apply_subscription_plan(env, token, duration, price)
+        }
+        AdminAction::AddSupportedToken(token) => apply_add_supported_token(env, token),
+        AdminAction::RemoveSupportedToken(token) => apply_remove_supported_token(env, token),
+        AdminAction::Pause => apply_pause(env),
+        AdminAction::Unpause => apply_unpause(env),
+        AdminAction::ProposeAdmin(new_admin, expires_at) => {
+            apply_propose_admin(env, get_admin(env.clone())?, new_admin, expires_at)
+        }
+        AdminAction::CancelAdminTransfer => apply_cancel_admin_transfer(env, actor.clone()),
+        AdminAction::GrantRole(role, account) => {
+            apply_grant_role(env, actor.clone(), role, account)
+        }
+        AdminAction::RevokeRole(role, account) => {
+            apply_revoke_role(env, actor.clone(), role, account)
+        }
+        AdminAction::SetSplitterWasmHash(wasm_hash) => apply_splitter_wasm_hash(env, wasm_hash),
+        AdminAction::SetCouncil(signers, threshold) => apply_set_council(env, signers, threshold),
+        AdminAction::DisableCouncil => apply_disable_council(env),
+        AdminAction::SetTimelockDelay(delay) => apply_timelock_delay(env, delay),
+        AdminAction::CancelQueuedAction(action_id) => {
+            apply_cancel_queued_action(env, action_id, actor.clone())
+        }
+    }
+}
+
+// ============================================================================
+// Timelock
+// ============================================================================
+
+/// Longest delay the timelock can be set to (30 days, in seconds).
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+
+/// Actions that must be queued and wait out the timelock delay while one is set.
+fn is_timelocked(action: &AdminAction) -> bool {
+    matches!(
+        action,
+        AdminAction::Withdraw(..)
+            | AdminAction::SetUsageFee(..)
+            | AdminAction::SetPricingTiers(_)
+            | AdminAction::SetSubscriptionPlan(..)
+            | AdminAction::SetProtocolFee(_)
+            | AdminAction::RemoveSupportedToken(_)
+            | AdminAction::SetTimelockDelay(_)
+    )
+}
+
+fn read_timelock_delay(env: &Env) -> u64 {
+    let delay_key = DataKey::TimelockDelay;
+    let result: Option<u64> = env.storage().persistent().get(&delay_key);
+    if result.is_some() {
+        bump_persistent(env, &delay_key);
+    }
+    result.unwrap_or(0)
+}
+
+/// Fails with `TimelockActive` while a delay is set, so timelocked actions go
+/// through `queue_action`.
+fn require_no_timelock(env: &Env) -> Result<(), Error> {
+    if read_timelock_delay(env) > 0 {
+        return Err(Error::TimelockActive);
+    }
+    Ok(())
+}
+
+/// Sets the delay, in seconds, between queuing and executing timelocked actions.
+/// Admin only. Once a delay is set, changing it is itself a timelocked action.
+pub fn set_timelock_delay(env: Env, admin: Address, delay: u64) -> Result<(), Error> {
+    admin.require_auth();
+    require_admin(&env, &admin)?;
+    require_no_timelock(&env)?;
+    apply_timelock_delay(&env, delay)
+}
+
+fn apply_timelock_delay(env: &Env, delay: u64) -> Result<(), Error> {
+    if delay > MAX_TIMELOCK_DELAY {
+        return Err(Error::InvalidInput);
+    }
+    let delay_key = DataKey::TimelockDelay;
+    env.storage().persistent().set(&delay_key, &delay);
+    bump_persistent(env, &delay_key);
+    Ok(())
+}
+
+pub fn get_timelock_delay(env: Env) -> u64 {
+    read_timelock_delay(&env)
+}
+
+/// Queues a timelocked action to run once the delay has passed. The caller needs
+/// the same role the direct entrypoint requires; changing the delay needs the admin.
+/// Returns the queued action id.
+pub fn queue_action(env: Env, caller: Address, action: AdminAction) -> Result<u32, Error> {
+    caller.require_auth();
+    match &action {
+        AdminAction::Withdraw(..) => require_role(&env, &caller, Role::Treasurer)?,
+        AdminAction::SetUsageFee(..)
+        | AdminAction::SetPricingTiers(_)
+        | AdminAction::SetSubscriptionPlan(..)
+        | AdminAction::SetProtocolFee(_) => require_role(&env, &caller, Role::FeeManager)?,
+        AdminAction::RemoveSupportedToken(_) => require_role(&env, &caller, Role::TokenManager)?,
+        AdminAction::SetTimelockDelay(_) => require_admin(&env, &caller)?,
+        _ => return Err(Error::InvalidInput),
+    }
+    Ok(enqueue_action(&env, action, caller))
+}
+
+fn enqueue_action(env: &Env, action: AdminAction, queued_by: Address) -> u32 {
+    let id_key = DataKey::NextQueuedActionId;
+    let action_id: u32 = env.storage().persistent().get(&id_key).unwrap_or(0);
+    env.storage().persistent().set(&id_key, &(action_id + 1));
+    bump_persistent(env, &id_key);
+
+    let eta = env.ledger().timestamp() + read_timelock_delay(env);
+    let queued_key = DataKey::QueuedAction(action_id);
+    env.storage().persistent().set(
+        &queued_key,
+        &QueuedAction {
+            id: action_id,
+            action,
+            eta,
+            queued_by: queued_by.clone(),
+        },
+    );
+    bump_persistent(env, &queued_key);
+
+    ActionQueued {
+        action_id,
+        queued_by,
+        eta,
+    }
+    .publish(env);
+    action_id
+}
+
+/// Runs a queued action once its ETA has passed. Anyone can call; the action was
+/// authorised when it was queued. While a council is set, only actions the
+/// council approved can run.
+pub fn execute_queued_action(env: Env, action_id: u32) -> Result<(), Error> {
+    let queued = get_queued_action(env.clone(), action_id)?;
+    if read_council(&env).is_some() && queued.queued_by != env.current_contract_address() {
+        return Err(Error::CouncilModeActive);
+    }
+    if env.ledger().timestamp() < queued.eta {
+        return Err(Error::TimelockNotReady);
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::QueuedAction(action_id));
+    execute_admin_action(&env, queued.action, &queued.queued_by)?;
+
+    QueuedActionExecuted { action_id }.publish(&env);
+    Ok(())
+}
+
+/// Drops a queued action before it runs. Admin only.
+pub fn cancel_queued_action(env: Env, admin: Address, action_id: u32) -> Result<(), Error> {
+    admin.require_auth();
+    require_admin(&env, &admin)?;
+    apply_cancel_queued_action(&env, action_id, admin)
+}
+
+fn apply_cancel_queued_action(env: &Env, action_id: u32, admin: Address) -> Result<(), Error> {
+    let queued_key = DataKey::QueuedAction(action_id);
+    if !env.storage().persistent().has(&queued_key) {
+        return Err(Error::NotFound);
+    }
+    env.storage().persistent().remove(&queued_key);
+
+    QueuedActionCancelled { action_id, admin }.publish(env);
+    Ok(())
+}
+
+pub fn get_queued_action(env: Env, action_id: u32) -> Result<QueuedAction, Error> {
+    let queued_key = DataKey::QueuedAction(action_id);
+    let queued: QueuedAction = env
+        .storage()
+        .persistent()
+        .get(&queued_key)
+        .ok_or(Error::NotFound)?;
+    bump_persistent(&env, &queued_key);
+    Ok(queued)
+}
+
+// ============================================================================
+// Pause Management
+// ============================================================================
+
+pub fn pause(env: Env, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::Pauser)?;
+
+    apply_pause(&env)
+}
+
+fn apply_pause(env: &Env) -> Result<(), Error> {
+    let pause_key = DataKey::IsPaused;
+    let is_paused: bool = env.storage().persistent().get(&pause_key).unwrap_or(false);
+    bump_persistent(env, &pause_key);
+
+    if is_paused {
+        return Err(Error::AlreadyPaused);
+    }
+
+    env.storage().persistent().set(&pause_key, &true);
+    bump_persistent(env, &pause_key);
+    ContractPaused {}.publish(env);
+    Ok(())
+}
+
+pub fn unpause(env: Env, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::Pauser)?;
+
+    apply_unpause(&env)
+}
+
+fn apply_unpause(env: &Env) -> Result<(), Error> {
+    let pause_key = DataKey::IsPaused;
+    let is_paused: bool = env.storage().persistent().get(&pause_key).unwrap_or(false);
+    bump_persistent(env, &pause_key);
+
+    if !is_paused {
+        return Err(Error::NotPaused);
+    }
+
+    env.storage().persistent().set(&pause_key, &false);
+    bump_persistent(env, &pause_key);
+    ContractUnpaused {}.publish(env);
     Ok(())
 }
 
@@ -446,18 +1483,22 @@ pub fn get_paused_status(env: &Env) -> bool {
 // Supported Tokens Management
 // ============================================================================
 
-pub fn add_supported_token(env: Env, token: Address, admin: Address) -> Result<(), Error> {
-    admin.require_auth();
-    require_admin(&env, &admin)?;
+pub fn add_supported_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::TokenManager)?;
 
+    apply_add_supported_token(&env, token)
+}
+
+fn apply_add_supported_token(env: &Env, token: Address) -> Result<(), Error> {
     let tokens_key = DataKey::SupportedTokens;
     let mut tokens: Vec<Address> = env
         .storage()
         .persistent()
         .get(&tokens_key)
-        .unwrap_or(Vec::new(&env));
+        .unwrap_or(Vec::new(env));
     if !tokens.is_empty() {
-        bump_persistent(&env, &tokens_key);
+        bump_persistent(env, &tokens_key);
     }
 
     // Check if token is already supported
@@ -469,25 +1510,30 @@ pub fn add_supported_token(env: Env, token: Address, admin: Address) -> Result<(
 
     tokens.push_back(token);
     env.storage().persistent().set(&tokens_key, &tokens);
-    bump_persistent(&env, &tokens_key);
+    bump_persistent(env, &tokens_key);
     Ok(())
 }
 
-pub fn remove_supported_token(env: Env, token: Address, admin: Address) -> Result<(), Error> {
-    admin.require_auth();
-    require_admin(&env, &admin)?;
+pub fn remove_supported_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::TokenManager)?;
+    require_no_timelock(&env)?;
+
+    apply_remove_supported_token(&env, token)
+}
 
+fn apply_remove_supported_token(env: &Env, token: Address) -> Result<(), Error> {
     let tokens_key = DataKey::SupportedTokens;
     let tokens: Vec<Address> = env
         .storage()
         .persistent()
         .get(&tokens_key)
-        .unwrap_or(Vec::new(&env));
+        .unwrap_or(Vec::new(env));
     if !tokens.is_empty() {
-        bump_persistent(&env, &tokens_key);
+        bump_persistent(env, &tokens_key);
     }
 
-    let mut new_tokens: Vec<Address> = Vec::new(&env);
+    let mut new_tokens: Vec<Address> = Vec::new(env);
     let mut found = false;
 
     for existing_token in tokens.iter() {
@@ -503,7 +1549,10 @@ pub fn remove_supported_token(env: Env, token: Address, admin: Address) -> Resul
     }
 
     env.storage().persistent().set(&tokens_key, &new_tokens);
-    bump_persistent(&env, &tokens_key);
+    bump_persistent(env, &tokens_key);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::TokenUsageFee(token));
     Ok(())
 }
 
@@ -530,26 +1579,110 @@ pub fn is_token_supported(env: Env, token: Address) -> bool {
 // Payment Configuration
 // ============================================================================
 
-pub fn set_usage_fee(env: Env, fee: u32, admin: Address) -> Result<(), Error> {
-    admin.require_auth();
-    require_admin(&env, &admin)?;
-    if fee == 0 {
+/// Sets the fee per usage charged in `token`, in the token's smallest units.
+pub fn set_usage_fee(env: Env, token: Address, fee: i128, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::FeeManager)?;
+    require_no_timelock(&env)?;
+    apply_usage_fee(&env, token, fee)
+}
+
+fn apply_usage_fee(env: &Env, token: Address, fee: i128) -> Result<(), Error> {
+    if fee <= 0 {
         return Err(Error::InvalidAmount);
     }
+    if !is_token_supported(env.clone(), token.clone()) {
+        return Err(Error::UnsupportedToken);
+    }
+
+    write_usage_fee(env, token, fee);
+    Ok(())
+}
 
-    let fee_key = DataKey::UsageFee;
+fn write_usage_fee(env: &Env, token: Address, fee: i128) {
+    let fee_key = DataKey::TokenUsageFee(token);
     env.storage().persistent().set(&fee_key, &fee);
+    bump_persistent(env, &fee_key);
+}
+
+/// Returns the fee per usage charged in `token`. Fails with `UsageFeeNotSet`
+/// when no fee is set for it, so usages cannot be bought in that token.
+pub fn get_usage_fee(env: Env, token: Address) -> Result<i128, Error> {
+    let fee_key = DataKey::TokenUsageFee(token);
+    let fee: i128 = env
+        .storage()
+        .persistent()
+        .get(&fee_key)
+        .ok_or(Error::UsageFeeNotSet)?;
     bump_persistent(&env, &fee_key);
+    Ok(fee)
+}
+
+/// Replaces the volume discounts applied to usage purchases. Tiers must be in
+/// ascending `min_usages` order with discounts below 100%. An empty list removes
+/// all discounts.
+pub fn set_pricing_tiers(env: Env, caller: Address, tiers: Vec<PricingTier>) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::FeeManager)?;
+    require_no_timelock(&env)?;
+    apply_pricing_tiers(&env, tiers)
+}
+
+fn apply_pricing_tiers(env: &Env, tiers: Vec<PricingTier>) -> Result<(), Error> {
+    let mut previous_min = 0u32;
+    for tier in tiers.iter() {
+        if tier.min_usages <= previous_min || tier.discount_bps >= TOTAL_BASIS_POINTS {
+            return Err(Error::InvalidInput);
+        }
+        previous_min = tier.min_usages;
+    }
+
+    let tiers_key = DataKey::PricingTiers;
+    env.storage().persistent().set(&tiers_key, &tiers);
+    bump_persistent(env, &tiers_key);
     Ok(())
 }
 
-pub fn get_usage_fee(env: Env) -> u32 {
-    let fee_key = DataKey::UsageFee;
-    let result: Option<u32> = env.storage().persistent().get(&fee_key);
+pub fn get_pricing_tiers(env: Env) -> Vec<PricingTier> {
+    let tiers_key = DataKey::PricingTiers;
+    let result: Option<Vec<PricingTier>> = env.storage().persistent().get(&tiers_key);
     if result.is_some() {
-        bump_persistent(&env, &fee_key);
+        bump_persistent(&env, &tiers_key);
+    }
+    result.unwrap_or(Vec::new(&env))
+}
+
+/// Returns what buying `count` usages in `token` costs now, after any volume discount.
+pub fn quote_usages(env: Env, token: Address, count: u32) -> Result<i128, Error> {
+    if count == 0 {
+        return Err(Error::InvalidUsageCount);
+    }
+    if !is_token_supported(env.clone(), token.clone()) {
+        return Err(Error::UnsupportedToken);
+    }
+    let usage_fee = get_usage_fee(env.clone(), token)?;
+    let (total_cost, _) = price_usages(&env, usage_fee, count);
+    Ok(total_cost)
+}
+
+/// Prices `count` usages at `unit_price` using the highest tier they reach.
+/// Returns the total cost and the discount applied in basis points.
+fn price_usages(env: &Env, unit_price: i128, count: u32) -> (i128, u32) {
+    let mut discount_bps = 0u32;
+    for tier in get_pricing_tiers(env.clone()).iter() {
+        if count >= tier.min_usages {
+            discount_bps = tier.discount_bps;
+        }
     }
-    result.unwrap_or(10u32)
+    (
+        apply_discount(count, unit_price, discount_bps),
+        discount_bps,
+    )
+}
+
+fn apply_discount(count: u32, unit_price: i128, discount_bps: u32) -> i128 {
+    let gross = (count as i128) * unit_price;
+    gross - gross * (discount_bps as i128) / (TOTAL_BASIS_POINTS as i128)
 }
 
 // ============================================================================
@@ -562,6 +1695,7 @@ pub fn topup_subscription(
     additional_usages: u32,
     payment_token: Address,
     payer: Address,
+    plan_duration: Option<u64>,
 ) -> Result<(), Error> {
     payer.require_auth();
 
@@ -570,18 +1704,14 @@ pub fn topup_subscription(
         return Err(Error::ContractPaused);
     }
 
-    // Validate usage count
-    if additional_usages == 0 {
+    // Validate usage count; a top-up may buy only time
+    if additional_usages == 0 && plan_duration.is_none() {
         return Err(Error::InvalidUsageCount);
     }
 
     // Verify group exists
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
     bump_persistent(&env, &key);
 
     // Verify token is supported
@@ -589,13 +1719,15 @@ pub fn topup_subscription(
         return Err(Error::UnsupportedToken);
     }
 
-    // Calculate cost
-    let usage_fee = get_usage_fee(env.clone());
-    let total_cost = (additional_usages as i128) * (usage_fee as i128);
-
-    // Transfer tokens from payer to contract
-    let token_client = token::Client::new(&env, &payment_token);
-    token_client.transfer(&payer, env.current_contract_address(), &total_cost);
+    // Charge the payer for the usages and plan
+    charge_purchase(
+        &env,
+        &id,
+        &payer,
+        &payment_token,
+        additional_usages,
+        plan_duration,
+    )?;
 
     // Update usage counts
     details.usage_count += additional_usages;
@@ -605,208 +1737,1843 @@ pub fn topup_subscription(
     env.storage().persistent().set(&key, &details);
     bump_persistent(&env, &key);
 
-    // Record payment history
-    record_payment(env, payer, id, additional_usages, total_cost);
+    Ok(())
+}
 
+/// Charges `payer` for `usages` usages and an optional time plan in `token`.
+/// Records the payments, the usage lot and the new subscription expiry. The
+/// plan is fee revenue at once; usages become revenue as they are spent.
+fn charge_purchase(
+    env: &Env,
+    id: &BytesN<32>,
+    payer: &Address,
+    token: &Address,
+    usages: u32,
+    plan_duration: Option<u64>,
+) -> Result<(), Error> {
+    let plan_price = match plan_duration {
+        Some(duration) => get_subscription_plan(env.clone(), token.clone(), duration)
+            .ok_or(Error::SubscriptionPlanNotFound)?,
+        None => 0,
+    };
+    // A purchase of time only needs no usage fee
+    let usage_fee = if usages > 0 {
+        get_usage_fee(env.clone(), token.clone())?
+    } else {
+        0
+    };
+    let (usage_cost, discount_bps) = price_usages(env, usage_fee, usages);
+    let total_cost = usage_cost + plan_price;
+
+    let token_client = token::Client::new(env, token);
+    token_client.transfer(payer, env.current_contract_address(), &total_cost);
+
+    // Usages are refundable, so keep the lot they were bought in
+    let mut refundable = 0;
+    if usages > 0 {
+        record_payment(
+            env.clone(),
+            payer.clone(),
+            id.clone(),
+            usages,
+            usage_cost,
+            discount_bps,
+        );
+        refundable = add_usage_lot(env, id, payer, token, usage_fee, discount_bps, usages);
+    }
+    credit_fee_revenue(env, token, total_cost - refundable);
+    if let Some(duration) = plan_duration {
+        record_payment(env.clone(), payer.clone(), id.clone(), 0, plan_price, 0);
+        extend_subscription(env, id, payer, duration);
+    }
     Ok(())
 }
 
 // ============================================================================
-// Payment History
+// Time Subscriptions
 // ============================================================================
 
-fn record_payment(
+/// Sets the price in `token` of a plan giving unlimited distributions for
+/// `duration` seconds, or removes the plan when `price` is `None`.
+pub fn set_subscription_plan(
     env: Env,
-    user: Address,
-    group_id: BytesN<32>,
-    usages_purchased: u32,
-    amount_paid: i128,
-) {
-    let timestamp = env.ledger().timestamp();
+    caller: Address,
+    token: Address,
+    duration: u64,
+    price: Option<i128>,
+) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::FeeManager)?;
+    require_no_timelock(&env)?;
+    apply_subscription_plan(&env, token, duration, price)
+}
 
-    let payment = PaymentHistory {
-        user: user.clone(),
-        group_id: group_id.clone(),
-        usages_purchased,
-        amount_paid,
-        timestamp,
-    };
+fn apply_subscription_plan(
+    env: &Env,
+    token: Address,
+    duration: u64,
+    price: Option<i128>,
+) -> Result<(), Error> {
+    if duration == 0 {
+        return Err(Error::InvalidInput);
+    }
 
-    // Add to user's payment history
-    let user_history_key = DataKey::UserPaymentHistory(user.clone());
-    let mut user_history: Vec<PaymentHistory> = env
+    let plan_key = DataKey::SubscriptionPlan(token.clone(), duration);
+    match price {
+        Some(price) => {
+            if price <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if !is_token_supported(env.clone(), token) {
+                return Err(Error::UnsupportedToken);
+            }
+            env.storage().persistent().set(&plan_key, &price);
+            bump_persistent(env, &plan_key);
+        }
+        None => {
+            if !env.storage().persistent().has(&plan_key) {
+                return Err(Error::SubscriptionPlanNotFound);
+            }
+            env.storage().persistent().remove(&plan_key);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the price in `token` of the plan lasting `duration` seconds, if offered.
+pub fn get_subscription_plan(env: Env, token: Address, duration: u64) -> Option<i128> {
+    let plan_key = DataKey::SubscriptionPlan(token, duration);
+    let result: Option<i128> = env.storage().persistent().get(&plan_key);
+    if result.is_some() {
+        bump_persistent(&env, &plan_key);
+    }
+    result
+}
+
+/// Returns when the group's time subscription ends, or 0 if it never had one.
+pub fn get_subscription_expiry(env: Env, id: BytesN<32>) -> Result<u64, Error> {
+    let key = DataKey::AutoShare(id.clone());
+    if !env.storage().persistent().has(&key) {
+        return Err(Error::NotFound);
+    }
+    bump_persistent(&env, &key);
+    Ok(read_subscription_expiry(&env, &id))
+}
+
+fn read_subscription_expiry(env: &Env, id: &BytesN<32>) -> u64 {
+    let expiry_key = DataKey::SubscriptionExpiry(id.clone());
+    let result: Option<u64> = env.storage().persistent().get(&expiry_key);
+    if result.is_some() {
+        bump_persistent(env, &expiry_key);
+    }
+    result.unwrap_or(0)
+}
+
+fn has_active_subscription(env: &Env, id: &BytesN<32>) -> bool {
+    read_subscription_expiry(env, id) > env.ledger().timestamp()
+}
+
+/// Adds `duration` seconds to the group's subscription, counting from now if
+/// it has lapsed.
+fn extend_subscription(env: &Env, id: &BytesN<32>, payer: &Address, duration: u64) {
+    let now = env.ledger().timestamp();
+    let expires_at = read_subscription_expiry(env, id)
+        .max(now)
+        .saturating_add(duration);
+
+    let expiry_key = DataKey::SubscriptionExpiry(id.clone());
+    env.storage().persistent().set(&expiry_key, &expires_at);
+    bump_persistent(env, &expiry_key);
+
+    SubscriptionPurchased {
+        id: id.clone(),
+        payer: payer.clone(),
+        expires_at,
+    }
+    .publish(env);
+}
+
+/// Spends one usage on a distribution. Nothing is spent while the group has
+/// an active time subscription or pays a percentage fee instead.
+fn spend_usage(env: &Env, id: &BytesN<32>, details: &mut AutoShareDetails) {
+    if pays_protocol_fee(env, id) || has_active_subscription(env, id) {
+        return;
+    }
+    consume_usage_lot(env, id, details.usage_count);
+    details.usage_count -= 1;
+}
+
+// ============================================================================
+// Protocol Fee
+// ============================================================================
+
+/// Sets the share of each distribution, in basis points, taken as fee revenue
+/// from groups in percentage mode.
+pub fn set_protocol_fee(env: Env, caller: Address, fee_bps: u32) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::FeeManager)?;
+    require_no_timelock(&env)?;
+    apply_protocol_fee(&env, fee_bps)
+}
+
+fn apply_protocol_fee(env: &Env, fee_bps: u32) -> Result<(), Error> {
+    if fee_bps >= TOTAL_BASIS_POINTS {
+        return Err(Error::InvalidInput);
+    }
+    let fee_key = DataKey::ProtocolFeeBps;
+    env.storage().persistent().set(&fee_key, &fee_bps);
+    bump_persistent(env, &fee_key);
+    Ok(())
+}
+
+pub fn get_protocol_fee(env: Env) -> u32 {
+    let fee_key = DataKey::ProtocolFeeBps;
+    let result: Option<u32> = env.storage().persistent().get(&fee_key);
+    if result.is_some() {
+        bump_persistent(&env, &fee_key);
+    }
+    result.unwrap_or(0)
+}
+
+/// Sets whether a group pays with prepaid usages or a percentage of each
+/// distribution. Only the creator can change it, and percentage mode needs a
+/// protocol fee to be set.
+pub fn set_fee_mode(env: Env, id: BytesN<32>, caller: Address, mode: FeeMode) -> Result<(), Error> {
+    caller.require_auth();
+
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
+    }
+
+    let key = DataKey::AutoShare(id.clone());
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+
+    if details.creator != caller {
+        return Err(Error::Unauthorized);
+    }
+
+    if mode == FeeMode::Percentage && get_protocol_fee(env.clone()) == 0 {
+        return Err(Error::ProtocolFeeNotSet);
+    }
+
+    let mode_key = DataKey::GroupFeeMode(id.clone());
+    env.storage().persistent().set(&mode_key, &mode);
+    bump_persistent(&env, &mode_key);
+
+    AutoshareUpdated {
+        id,
+        updater: caller,
+    }
+    .publish(&env);
+    Ok(())
+}
+
+pub fn get_fee_mode(env: Env, id: BytesN<32>) -> Result<FeeMode, Error> {
+    let key = DataKey::AutoShare(id.clone());
+    if !env.storage().persistent().has(&key) {
+        return Err(Error::NotFound);
+    }
+    bump_persistent(&env, &key);
+
+    Ok(read_fee_mode(&env, &id))
+}
+
+fn read_fee_mode(env: &Env, id: &BytesN<32>) -> FeeMode {
+    let mode_key = DataKey::GroupFeeMode(id.clone());
+    let result: Option<FeeMode> = env.storage().persistent().get(&mode_key);
+    if result.is_some() {
+        bump_persistent(env, &mode_key);
+    }
+    result.unwrap_or(FeeMode::Prepaid)
+}
+
+/// Whether the group's distributions pay the protocol fee instead of spending
+/// usages. A percentage-mode group spends usages again while the fee is 0.
+fn pays_protocol_fee(env: &Env, id: &BytesN<32>) -> bool {
+    read_fee_mode(env, id) == FeeMode::Percentage && get_protocol_fee(env.clone()) > 0
+}
+
+/// Returns the protocol fee taken from a distribution of `amount` to the group.
+fn distribution_fee(env: &Env, id: &BytesN<32>, amount: i128) -> i128 {
+    if !pays_protocol_fee(env, id) {
+        return 0;
+    }
+    amount * (get_protocol_fee(env.clone()) as i128) / (TOTAL_BASIS_POINTS as i128)
+}
+
+// ============================================================================
+// Usage Lots and Refunds
+// ============================================================================
+
+fn read_usage_lots(env: &Env, id: &BytesN<32>) -> Vec<UsageLot> {
+    let lots_key = DataKey::GroupUsageLots(id.clone());
+    let result: Option<Vec<UsageLot>> = env.storage().persistent().get(&lots_key);
+    if result.is_some() {
+        bump_persistent(env, &lots_key);
+    }
+    result.unwrap_or(Vec::new(env))
+}
+
+fn write_usage_lots(env: &Env, id: &BytesN<32>, lots: &Vec<UsageLot>) {
+    let lots_key = DataKey::GroupUsageLots(id.clone());
+    env.storage().persistent().set(&lots_key, lots);
+    bump_persistent(env, &lots_key);
+}
+
+/// Records prepaid usages with the price, discount and token they were paid with.
+/// A purchase matching the newest lot is merged into it. Returns how much the
+/// refundable value of the lots grew.
+fn add_usage_lot(
+    env: &Env,
+    id: &BytesN<32>,
+    payer: &Address,
+    token: &Address,
+    unit_price: i128,
+    discount_bps: u32,
+    usages: u32,
+) -> i128 {
+    let mut lots = read_usage_lots(env, id);
+    if let Some(mut last) = lots.last() {
+        if last.payer == *payer
+            && last.token == *token
+            && last.unit_price == unit_price
+            && last.discount_bps == discount_bps
+        {
+            let before = lot_value(&last);
+            last.remaining += usages;
+            let added = lot_value(&last) - before;
+            lots.set(lots.len() - 1, last);
+            write_usage_lots(env, id, &lots);
+            add_token_liabilities(env, token, added);
+            return added;
+        }
+    }
+    let lot = UsageLot {
+        payer: payer.clone(),
+        token: token.clone(),
+        unit_price,
+        discount_bps,
+        remaining: usages,
+    };
+    let added = lot_value(&lot);
+    lots.push_back(lot);
+    write_usage_lots(env, id, &lots);
+    add_token_liabilities(env, token, added);
+    added
+}
+
+/// What the lot's remaining usages would be refunded for.
+fn lot_value(lot: &UsageLot) -> i128 {
+    apply_discount(lot.remaining, lot.unit_price, lot.discount_bps)
+}
+
+/// Takes one usage out of the group's lots, oldest first, and credits what it
+/// was paid for to the fee revenue. `usage_count` is the count before the usage
+/// is spent. Usages bought before lots were tracked have no lot and are spent
+/// first; their price is part of the revenue `migrate_fee_revenue` recovers.
+fn consume_usage_lot(env: &Env, id: &BytesN<32>, usage_count: u32) {
+    let mut lots = read_usage_lots(env, id);
+    let tracked: u32 = lots.iter().map(|lot| lot.remaining).sum();
+    if usage_count > tracked {
+        return;
+    }
+
+    if let Some(mut first) = lots.first() {
+        let before = lot_value(&first);
+        first.remaining -= 1;
+        let spent = before - lot_value(&first);
+        add_token_liabilities(env, &first.token, -spent);
+        credit_fee_revenue(env, &first.token, spent);
+        if first.remaining == 0 {
+            lots.pop_front();
+        } else {
+            lots.set(0, first);
+        }
+        write_usage_lots(env, id, &lots);
+    }
+}
+
+/// Pays every remaining lot back to its payer and records the refunds as
+/// negative payments. Unspent usages were never fee revenue, so withdrawals
+/// cannot block refunds. Usages with no lot are forfeited.
+fn refund_usage_lots(env: &Env, id: &BytesN<32>) {
+    let lots = read_usage_lots(env, id);
+    for lot in lots.iter() {
+        if lot.remaining == 0 {
+            continue;
+        }
+        let amount = lot_value(&lot);
+
+        let client = token::TokenClient::new(env, &lot.token);
+        client.transfer(&env.current_contract_address(), &lot.payer, &amount);
+        add_token_liabilities(env, &lot.token, -amount);
+        record_payment(
+            env.clone(),
+            lot.payer.clone(),
+            id.clone(),
+            lot.remaining,
+            -amount,
+            lot.discount_bps,
+        );
+
+        UsagesRefunded {
+            payer: lot.payer,
+            id: id.clone(),
+            token: lot.token,
+            amount,
+        }
+        .publish(env);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupUsageLots(id.clone()));
+}
+
+// ============================================================================
+// Payment History
+// ============================================================================
+
+fn record_payment(
+    env: Env,
+    user: Address,
+    group_id: BytesN<32>,
+    usages_purchased: u32,
+    amount_paid: i128,
+    discount_bps: u32,
+) {
+    let timestamp = env.ledger().timestamp();
+
+    let payment = PaymentHistory {
+        user: user.clone(),
+        group_id: group_id.clone(),
+        usages_purchased,
+        amount_paid,
+        timestamp,
+        discount_bps,
+    };
+
+    append_history(&env, &History::UserPayments(user), &payment);
+    append_history(&env, &History::GroupPayments(group_id), &payment);
+}
+
+pub fn get_user_payment_history(env: Env, user: Address) -> Vec<PaymentHistory> {
+    read_history(&env, &History::UserPayments(user))
+}
+
+pub fn get_group_payment_history(env: Env, id: BytesN<32>) -> Vec<PaymentHistory> {
+    read_history(&env, &History::GroupPayments(id))
+}
+
+/// Returns up to `limit` payments of `user` starting at position `start`, oldest first.
+pub fn get_user_payment_history_page(
+    env: Env,
+    user: Address,
+    start: u32,
+    limit: u32,
+) -> Result<PaymentHistoryPage, Error> {
+    let (items, next_cursor) = read_history_page(&env, &History::UserPayments(user), start, limit)?;
+    Ok(PaymentHistoryPage { items, next_cursor })
+}
+
+/// Returns up to `limit` payments for group `id` starting at position `start`, oldest first.
+pub fn get_group_payment_history_page(
+    env: Env,
+    id: BytesN<32>,
+    start: u32,
+    limit: u32,
+) -> Result<PaymentHistoryPage, Error> {
+    let (items, next_cursor) = read_history_page(&env, &History::GroupPayments(id), start, limit)?;
+    Ok(PaymentHistoryPage { items, next_cursor })
+}
+
+// ============================================================================
+// Distribution History
+// ============================================================================
+
+fn record_distribution(env: Env, distribution: DistributionHistory) {
+    append_history(
+        &env,
+        &History::GroupDistributions(distribution.group_id.clone()),
+        &distribution,
+    );
+    // A member paid in several tokens gets one entry
+    let mut members: Vec<Address> = Vec::new(&env);
+    for member_amount in distribution.member_amounts.iter() {
+        members.push_back(member_amount.address);
+    }
+    for other in distribution.other_tokens.iter() {
+        for member_amount in other.member_amounts.iter() {
+            if !members.contains(&member_amount.address) {
+                members.push_back(member_amount.address);
+            }
+        }
+    }
+    for member in members.iter() {
+        append_history(&env, &History::MemberDistributions(member), &distribution);
+    }
+}
+
+pub fn get_group_distributions(env: Env, id: BytesN<32>) -> Vec<DistributionHistory> {
+    read_history(&env, &History::GroupDistributions(id))
+}
+
+pub fn get_member_distributions(env: Env, member: Address) -> Vec<DistributionHistory> {
+    read_history(&env, &History::MemberDistributions(member))
+}
+
+/// Returns up to `limit` distributions of group `id` starting at position `start`, oldest first.
+pub fn get_group_distributions_page(
+    env: Env,
+    id: BytesN<32>,
+    start: u32,
+    limit: u32,
+) -> Result<DistributionHistoryPage, Error> {
+    let (items, next_cursor) =
+        read_history_page(&env, &History::GroupDistributions(id), start, limit)?;
+    Ok(DistributionHistoryPage { items, next_cursor })
+}
+
+/// Returns up to `limit` distributions paid to `member` starting at position `start`, oldest first.
+pub fn get_member_distributions_page(
+    env: Env,
+    member: Address,
+    start: u32,
+    limit: u32,
+) -> Result<DistributionHistoryPage, Error> {
+    let (items, next_cursor) =
+        read_history_page(&env, &History::MemberDistributions(member), start, limit)?;
+    Ok(DistributionHistoryPage { items, next_cursor })
+}
+
+// ============================================================================
+// History Storage
+// ============================================================================
+
+/// A history stored as one entry per record, numbered from 0, with its length
+/// kept under a separate counter.
+enum History {
+    UserPayments(Address),
+    GroupPayments(BytesN<32>),
+    GroupDistributions(BytesN<32>),
+    MemberDistributions(Address),
+}
+
+impl History {
+    fn count_key(&self) -> DataKey {
+        match self {
+            History::UserPayments(user) => DataKey::UserPaymentCount(user.clone()),
+            History::GroupPayments(id) => DataKey::GroupPaymentCount(id.clone()),
+            History::GroupDistributions(id) => DataKey::GroupDistributionCount(id.clone()),
+            History::MemberDistributions(member) => {
+                DataKey::MemberDistributionCount(member.clone())
+            }
+        }
+    }
+
+    fn entry_key(&self, seq: u32) -> DataKey {
+        match self {
+            History::UserPayments(user) => DataKey::UserPayment(user.clone(), seq),
+            History::GroupPayments(id) => DataKey::GroupPayment(id.clone(), seq),
+            History::GroupDistributions(id) => DataKey::GroupDistribution(id.clone(), seq),
+            History::MemberDistributions(member) => {
+                DataKey::MemberDistribution(member.clone(), seq)
+            }
+        }
+    }
+
+    fn legacy_key(&self) -> DataKey {
+        match self {
+            History::UserPayments(user) => DataKey::UserPaymentHistory(user.clone()),
+            History::GroupPayments(id) => DataKey::GroupPaymentHistory(id.clone()),
+            History::GroupDistributions(id) => DataKey::GroupDistributionHistory(id.clone()),
+            History::MemberDistributions(member) => {
+                DataKey::MemberDistributionHistory(member.clone())
+            }
+        }
+    }
+}
+
+/// A history record. Legacy vectors hold records in the layout they had
+/// before histories were numbered, which is upgraded as they are read.
+trait HistoryRecord: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone {
+    type Legacy: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone;
+
+    fn from_legacy(legacy: Self::Legacy) -> Self;
+}
+
+/// `PaymentHistory` as stored in legacy vectors.
+#[contracttype]
+#[derive(Clone)]
+struct LegacyPaymentHistory {
+    user: Address,
+    group_id: BytesN<32>,
+    usages_purchased: u32,
+    amount_paid: i128,
+    timestamp: u64,
+}
+
+impl HistoryRecord for PaymentHistory {
+    type Legacy = LegacyPaymentHistory;
+
+    fn from_legacy(legacy: LegacyPaymentHistory) -> Self {
+        PaymentHistory {
+            user: legacy.user,
+            group_id: legacy.group_id,
+            usages_purchased: legacy.usages_purchased,
+            amount_paid: legacy.amount_paid,
+            timestamp: legacy.timestamp,
+            discount_bps: 0,
+        }
+    }
+}
+
+/// `DistributionHistory` as stored in legacy vectors.
+#[contracttype]
+#[derive(Clone)]
+struct LegacyDistributionHistory {
+    group_id: BytesN<32>,
+    sender: Address,
+    total_amount: i128,
+    token: Address,
+    member_amounts: Vec<MemberAmount>,
+    timestamp: u64,
+    distribution_number: u32,
+}
+
+impl HistoryRecord for DistributionHistory {
+    type Legacy = LegacyDistributionHistory;
+
+    fn from_legacy(legacy: LegacyDistributionHistory) -> Self {
+        let env = legacy.member_amounts.env().clone();
+        DistributionHistory {
+            group_id: legacy.group_id,
+            sender: legacy.sender,
+            total_amount: legacy.total_amount,
+            token: legacy.token,
+            member_amounts: legacy.member_amounts,
+            timestamp: legacy.timestamp,
+            distribution_number: legacy.distribution_number,
+            is_deposit: false,
+            fee_amount: 0,
+            tranches: Vec::new(&env),
+            parent_group: None,
+            other_tokens: Vec::new(&env),
+        }
+    }
+}
+
+fn read_history_count(env: &Env, history: &History) -> Option<u32> {
+    let count_key = history.count_key();
+    let count: Option<u32> = env.storage().persistent().get(&count_key);
+    if count.is_some() {
+        bump_persistent(env, &count_key);
+    }
+    count
+}
+
+fn append_history<T>(env: &Env, history: &History, entry: &T)
+where
+    T: HistoryRecord,
+{
+    let count = match read_history_count(env, history) {
+        Some(count) => count,
+        None => migrate_legacy_history::<T>(env, history),
+    };
+
+    let entry_key = history.entry_key(count);
+    env.storage().persistent().set(&entry_key, entry);
+    bump_persistent(env, &entry_key);
+
+    let count_key = history.count_key();
+    env.storage().persistent().set(&count_key, &(count + 1));
+    bump_persistent(env, &count_key);
+}
+
+/// Reads entries `start..end` of a history. Histories that have not been
+/// migrated yet are served from their legacy vector.
+fn read_history_range<T>(env: &Env, history: &History, start: u32, end: u32) -> Vec<T>
+where
+    T: HistoryRecord,
+{
+    let mut result = Vec::new(env);
+    if read_history_count(env, history).is_none() {
+        let legacy: Vec<T> = read_legacy_history(env, history);
+        return legacy.slice(start.min(legacy.len())..end.min(legacy.len()));
+    }
+    for seq in start..end {
+        let entry_key = history.entry_key(seq);
+        if let Some(entry) = env.storage().persistent().get::<_, T>(&entry_key) {
+            bump_persistent(env, &entry_key);
+            result.push_back(entry);
+        }
+    }
+    result
+}
+
+fn history_len<T>(env: &Env, history: &History) -> u32
+where
+    T: HistoryRecord,
+{
+    match read_history_count(env, history) {
+        Some(count) => count,
+        None => read_legacy_history::<T>(env, history).len(),
+    }
+}
+
+fn read_history<T>(env: &Env, history: &History) -> Vec<T>
+where
+    T: HistoryRecord,
+{
+    let len = history_len::<T>(env, history);
+    read_history_range(env, history, 0, len)
+}
+
+fn read_history_page<T>(
+    env: &Env,
+    history: &History,
+    start: u32,
+    limit: u32,
+) -> Result<(Vec<T>, Option<u32>), Error>
+where
+    T: HistoryRecord,
+{
+    let len = history_len::<T>(env, history);
+    let (start, end, next_cursor) = page_bounds(len, start, limit)?;
+    Ok((read_history_range(env, history, start, end), next_cursor))
+}
+
+fn read_legacy_history<T>(env: &Env, history: &History) -> Vec<T>
+where
+    T: HistoryRecord,
+{
+    let legacy: Vec<T::Legacy> = env
         .storage()
         .persistent()
-        .get(&user_history_key)
-        .unwrap_or(Vec::new(&env));
-    if !user_history.is_empty() {
-        bump_persistent(&env, &user_history_key);
+        .get(&history.legacy_key())
+        .unwrap_or(Vec::new(env));
+    let mut entries = Vec::new(env);
+    for entry in legacy.iter() {
+        entries.push_back(T::from_legacy(entry));
+    }
+    entries
+}
+
+/// Moves a legacy vector history into numbered entries and returns the number
+/// of entries moved. Histories with no legacy vector are left untouched.
+fn migrate_legacy_history<T>(env: &Env, history: &History) -> u32
+where
+    T: HistoryRecord,
+{
+    let legacy_key = history.legacy_key();
+    let legacy: Vec<T::Legacy> = match env.storage().persistent().get(&legacy_key) {
+        Some(legacy) => legacy,
+        None => return 0,
+    };
+
+    let mut count = 0u32;
+    for entry in legacy.iter() {
+        let entry_key = history.entry_key(count);
+        env.storage()
+            .persistent()
+            .set(&entry_key, &T::from_legacy(entry));
+        bump_persistent(env, &entry_key);
+        count += 1;
+    }
+
+    let count_key = history.count_key();
+    env.storage().persistent().set(&count_key, &count);
+    bump_persistent(env, &count_key);
+    env.storage().persistent().remove(&legacy_key);
+    count
+}
+
+/// Moves the legacy vector histories of the given groups and accounts into
+/// numbered entries. Admin only. Histories already migrated are skipped.
+/// Returns the number of entries moved.
+pub fn migrate_history(
+    env: Env,
+    admin: Address,
+    group_ids: Vec<BytesN<32>>,
+    accounts: Vec<Address>,
+) -> Result<u32, Error> {
+    admin.require_auth();
+    require_admin_key(&env, &admin)?;
+
+    let mut moved = 0u32;
+    for id in group_ids.iter() {
+        let payments = History::GroupPayments(id.clone());
+        if read_history_count(&env, &payments).is_none() {
+            moved += migrate_legacy_history::<PaymentHistory>(&env, &payments);
+        }
+        let distributions = History::GroupDistributions(id);
+        if read_history_count(&env, &distributions).is_none() {
+            moved += migrate_legacy_history::<DistributionHistory>(&env, &distributions);
+        }
+    }
+    for account in accounts.iter() {
+        let payments = History::UserPayments(account.clone());
+        if read_history_count(&env, &payments).is_none() {
+            moved += migrate_legacy_history::<PaymentHistory>(&env, &payments);
+        }
+        let distributions = History::MemberDistributions(account);
+        if read_history_count(&env, &distributions).is_none() {
+            moved += migrate_legacy_history::<DistributionHistory>(&env, &distributions);
+        }
+    }
+    Ok(moved)
+}
+
+// ============================================================================
+// Usage Tracking
+// ============================================================================
+
+pub fn get_remaining_usages(env: Env, id: BytesN<32>) -> Result<u32, Error> {
+    let key = DataKey::AutoShare(id);
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+    Ok(details.usage_count)
+}
+
+pub fn get_total_usages_paid(env: Env, id: BytesN<32>) -> Result<u32, Error> {
+    let key = DataKey::AutoShare(id);
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+    Ok(details.total_usages_paid)
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+pub fn reduce_usage(env: Env, id: BytesN<32>) -> Result<(), Error> {
+    let key = DataKey::AutoShare(id.clone());
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+
+    if details.usage_count == 0 {
+        return Err(Error::NoUsagesRemaining);
+    }
+
+    consume_usage_lot(&env, &id, details.usage_count);
+    details.usage_count -= 1;
+    env.storage().persistent().set(&key, &details);
+    bump_persistent(&env, &key);
+    Ok(())
+}
+
+// ============================================================================
+// Group Activation Management
+// ============================================================================
+
+pub fn update_members(
+    env: Env,
+    id: BytesN<32>,
+    caller: Address,
+    new_members: Vec<GroupMember>,
+) -> Result<(), Error> {
+    caller.require_auth();
+
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
+    }
+
+    let key = DataKey::AutoShare(id.clone());
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+
+    if details.creator != caller {
+        return Err(Error::Unauthorized);
+    }
+
+    if !details.is_active {
+        return Err(Error::GroupInactive);
+    }
+
+    // Validate new members
+    validate_members(&new_members)?;
+    validate_child_groups(&env, &id, &new_members)?;
+
+    // Keep the member index in sync with the replaced list
+    let tranches = read_tranches(&env, &id);
+    update_member_index(
+        &env,
+        &id,
+        &group_payees(&env, &details.members, &tranches),
+        &group_payees(&env, &new_members, &tranches),
+    );
+    update_parent_index(
+        &env,
+        &id,
+        &child_group_ids(&env, &details.members),
+        &child_group_ids(&env, &new_members),
+    );
+
+    // Update members in details
+    details.members = new_members.clone();
+    env.storage().persistent().set(&key, &details);
+    bump_persistent(&env, &key);
+
+    // Also update the GroupMembers storage
+    let members_key = DataKey::GroupMembers(id.clone());
+    env.storage().persistent().set(&members_key, &new_members);
+    bump_persistent(&env, &members_key);
+
+    AutoshareUpdated {
+        id: id.clone(),
+        updater: caller,
+    }
+    .publish(&env);
+    Ok(())
+}
+
+/// Rescales member splits of groups stored before basis-point precision (sum = 100)
+/// to basis points (sum = 10_000) and rewrites them in the current layout. Admin only.
+/// Only groups still in the legacy layout are touched, so the call is safe to repeat
+/// and never rescales a basis-point group. Returns the number of groups migrated.
+pub fn migrate_member_splits(env: Env, admin: Address, ids: Vec<BytesN<32>>) -> Result<u32, Error> {
+    admin.require_auth();
+    require_admin_key(&env, &admin)?;
+
+    let scale = TOTAL_BASIS_POINTS / LEGACY_TOTAL_PERCENTAGE;
+    let mut migrated: u32 = 0;
+
+    for id in ids.iter() {
+        let key = DataKey::AutoShare(id.clone());
+        if !has_legacy_members(&env, &key) {
+            continue;
+        }
+        let mut details: AutoShareDetails = match read_details(&env, &key) {
+            Some(details) => details,
+            None => continue,
+        };
+        bump_persistent(&env, &key);
+
+        let mut total: u32 = 0;
+        for member in details.members.iter() {
+            total = total.saturating_add(member.percentage);
+        }
+        if details.members.is_empty() || total != LEGACY_TOTAL_PERCENTAGE {
+            continue;
+        }
+
+        let mut scaled: Vec<GroupMember> = Vec::new(&env);
+        for member in details.members.iter() {
+            scaled.push_back(GroupMember {
+                address: member.address.clone(),
+                percentage: member.percentage * scale,
+                fixed_amount: member.fixed_amount,
+                fixed_token: member.fixed_token,
+                group: member.group,
+            });
+        }
+
+        details.members = scaled.clone();
+        env.storage().persistent().set(&key, &details);
+        bump_persistent(&env, &key);
+
+        let members_key = DataKey::GroupMembers(id.clone());
+        env.storage().persistent().set(&members_key, &scaled);
+        bump_persistent(&env, &members_key);
+
+        migrated += 1;
+    }
+
+    Ok(migrated)
+}
+
+pub fn deactivate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
+    }
+
+    let key = DataKey::AutoShare(id.clone());
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+
+    if details.creator != caller {
+        return Err(Error::Unauthorized);
+    }
+
+    if !details.is_active {
+        return Err(Error::GroupAlreadyInactive);
+    }
+
+    details.is_active = false;
+    env.storage().persistent().set(&key, &details);
+    bump_persistent(&env, &key);
+
+    GroupDeactivated {
+        id: id.clone(),
+        creator: caller,
+    }
+    .publish(&env);
+    Ok(())
+}
+
+pub fn activate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
+    }
+
+    let key = DataKey::AutoShare(id.clone());
+    let mut details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+
+    if details.creator != caller {
+        return Err(Error::Unauthorized);
+    }
+
+    if details.is_active {
+        return Err(Error::GroupAlreadyActive);
+    }
+
+    details.is_active = true;
+    env.storage().persistent().set(&key, &details);
+    bump_persistent(&env, &key);
+
+    GroupActivated {
+        id: id.clone(),
+        creator: caller,
+    }
+    .publish(&env);
+    Ok(())
+}
+
+pub fn is_group_active(env: Env, id: BytesN<32>) -> Result<bool, Error> {
+    let key = DataKey::AutoShare(id);
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(&env, &key);
+    Ok(details.is_active)
+}
+
+// ============================================================================
+// Group Deletion
+// ============================================================================
+
+/// Permanently deletes a group from the contract.
+/// Requirements:
+/// 1. Caller must be the group creator or admin
+/// 2. Group must be deactivated and no other group may nest it
+/// 3. Refunds remaining usages to their payers at the price paid
+/// 4. Removes group from the list of all groups and its creator's list
+/// 5. Removes AutoShare(id) entry
+/// 6. Removes GroupMembers(id) entry
+/// 7. Archives payment history before deletion (keeps it for audit trail)
+/// 8. Emits GroupDeleted event
+///
+/// The group's splitter stays registered, so its id cannot be created again.
+pub fn delete_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+    caller.require_auth();
+
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
+    }
+
+    // Step 1: Verify group exists
+    let key = DataKey::AutoShare(id.clone());
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
+    // We don't bump here if we are removing it, but we might return early if deactivated check fails
+    // However, the requirement says bump on every read.
+    bump_persistent(&env, &key);
+
+    // Step 2: Verify caller is creator or admin
+    let admin_result = get_admin(env.clone());
+    let is_admin = admin_result.is_ok() && admin_result.unwrap() == caller;
+    let is_creator = details.creator == caller;
+
+    if !is_creator && !is_admin {
+        return Err(Error::Unauthorized);
+    }
+
+    // Step 3: Check group is already deactivated
+    if details.is_active {
+        return Err(Error::GroupNotDeactivated);
+    }
+
+    // A group that other groups pass shares down to must outlive them, or its
+    // id could be created again and take their members' shares
+    let parents_key = DataKey::ParentGroups(id.clone());
+    if env.storage().persistent().has(&parents_key) {
+        bump_persistent(&env, &parents_key);
+        return Err(Error::GroupReferenced);
+    }
+
+    // Step 4: Refund remaining usages to whoever paid for them, at the price and
+    // in the token they were paid with. Usages bought before lots were tracked
+    // are forfeited.
+    refund_usage_lots(&env, &id);
+
+    // Step 5: Remove the group from the list of all groups and its creator's list
+    remove_from_group_list(&env, &GroupList::All, &id);
+    remove_from_group_list(&env, &GroupList::Creator(details.creator.clone()), &id);
+
+    // Step 6: Remove the AutoShare(id) entry
+    env.storage().persistent().remove(&key);
+
+    // Step 7: Remove GroupMembers(id) entry and the group from each member's index
+    let payees = group_payees(&env, &details.members, &read_tranches(&env, &id));
+    let members_key = DataKey::GroupMembers(id.clone());
+    env.storage().persistent().remove(&members_key);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::SubscriptionExpiry(id.clone()));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupDistributionMode(id.clone()));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupFeeMode(id.clone()));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupTranches(id.clone()));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupReceived(id.clone()));
+    for address in payees.iter() {
+        remove_from_group_index(&env, &DataKey::MemberGroups(address), &id);
+    }
+    update_parent_index(
+        &env,
+        &id,
+        &child_group_ids(&env, &details.members),
+        &Vec::new(&env),
+    );
+
+    // Step 8: Archive payment history (we keep it for audit trail)
+    // Payment history is intentionally NOT deleted to maintain financial records
+    // This is a best practice for compliance and auditing purposes
+    // The entries remain in:
+    // - DataKey::UserPayment(Address, u32)
+    // - DataKey::GroupPayment(BytesN<32>, u32)
+
+    // Step 9: Emit deletion event
+    GroupDeleted {
+        deleter: caller,
+        id: id.clone(),
+    }
+    .publish(&env);
+
+    Ok(())
+}
+
+// ============================================================================
+// Contract Balance & Withdrawal
+// ============================================================================
+
+pub fn get_contract_balance(env: Env, token: Address) -> i128 {
+    let client = token::TokenClient::new(&env, &token);
+    client.balance(&env.current_contract_address())
+}
+
+/// Returns the fees collected in `token` that have not been withdrawn. Prepaid
+/// usages are counted as they are spent, since unspent ones can be refunded.
+pub fn get_fee_revenue(env: Env, token: Address) -> i128 {
+    let revenue_key = DataKey::FeeRevenue(token);
+    let result: Option<i128> = env.storage().persistent().get(&revenue_key);
+    if result.is_some() {
+        bump_persistent(&env, &revenue_key);
+    }
+    result.unwrap_or(0)
+}
+
+fn debit_fee_revenue(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+    let revenue = get_fee_revenue(env.clone(), token.clone());
+    if revenue < amount {
+        return Err(Error::InsufficientFeeRevenue);
+    }
+    let revenue_key = DataKey::FeeRevenue(token.clone());
+    env.storage()
+        .persistent()
+        .set(&revenue_key, &(revenue - amount));
+    bump_persistent(env, &revenue_key);
+    Ok(())
+}
+
+fn credit_fee_revenue(env: &Env, token: &Address, amount: i128) {
+    let revenue = get_fee_revenue(env.clone(), token.clone());
+    let revenue_key = DataKey::FeeRevenue(token.clone());
+    env.storage()
+        .persistent()
+        .set(&revenue_key, &(revenue + amount));
+    bump_persistent(env, &revenue_key);
+}
+
+/// Returns how much of the contract's `token` balance is owed to payers and
+/// members, as refundable usage lots and claimable balances.
+fn read_token_liabilities(env: &Env, token: &Address) -> i128 {
+    let liabilities_key = DataKey::TokenLiabilities(token.clone());
+    let result: Option<i128> = env.storage().persistent().get(&liabilities_key);
+    if result.is_some() {
+        bump_persistent(env, &liabilities_key);
+    }
+    result.unwrap_or(0)
+}
+
+fn add_token_liabilities(env: &Env, token: &Address, amount: i128) {
+    let liabilities = read_token_liabilities(env, token);
+    let liabilities_key = DataKey::TokenLiabilities(token.clone());
+    env.storage()
+        .persistent()
+        .set(&liabilities_key, &(liabilities + amount));
+    bump_persistent(env, &liabilities_key);
+}
+
+/// Credits the part of the contract's `token` balance that is neither fee
+/// revenue nor owed to payers or members to the fee revenue, so fees collected
+/// before revenue was tracked can be withdrawn. Admin only. Safe to repeat.
+/// Returns the amount credited.
+pub fn migrate_fee_revenue(env: Env, admin: Address, token: Address) -> Result<i128, Error> {
+    admin.require_auth();
+    require_admin_key(&env, &admin)?;
+
+    let untracked = get_contract_balance(env.clone(), token.clone())
+        - get_fee_revenue(env.clone(), token.clone())
+        - read_token_liabilities(&env, &token);
+    if untracked <= 0 {
+        return Ok(0);
+    }
+    credit_fee_revenue(&env, &token, untracked);
+    Ok(untracked)
+}
+
+pub fn withdraw(
+    env: Env,
+    caller: Address,
+    token: Address,
+    amount: i128,
+    recipient: Address,
+) -> Result<(), Error> {
+    caller.require_auth();
+    require_role(&env, &caller, Role::Treasurer)?;
+    require_no_timelock(&env)?;
+
+    apply_withdraw(&env, token, amount, recipient)
+}
+
+fn apply_withdraw(
+    env: &Env,
+    token: Address,
+    amount: i128,
+    recipient: Address,
+) -> Result<(), Error> {
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    // Only fee revenue can leave; the rest of the balance belongs to groups and members
+    debit_fee_revenue(env, &token, amount)?;
+
+    let contract_balance = get_contract_balance(env.clone(), token.clone());
+    if contract_balance < amount {
+        return Err(Error::InsufficientContractBalance);
+    }
+
+    let client = token::TokenClient::new(env, &token);
+    client.transfer(&env.current_contract_address(), &recipient, &amount);
+
+    Withdrawal {
+        token,
+        amount,
+        recipient,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Returns what each member would receive if `amount` of `token` were distributed
+/// to the group now, after any protocol fee. Runs the same checks as `distribute`
+/// and fails with the same errors. Shares of child-group members are shown under
+/// the member's address, before the child group splits them.
+pub fn preview_distribution(
+    env: Env,
+    id: BytesN<32>,
+    token: Address,
+    amount: i128,
+) -> Result<Vec<MemberAmount>, Error> {
+    let amounts = Vec::from_array(&env, [TokenAmount { token, amount }]);
+    let (_, splits) = prepare_distribution(&env, &id, &amounts)?;
+    Ok(splits.get_unchecked(0).member_amounts)
+}
+
+/// Validates a distribution of `amounts` to the group and splits each token.
+/// Shared by `distribute` and `preview_distribution`.
+fn prepare_distribution(
+    env: &Env,
+    id: &BytesN<32>,
+    amounts: &Vec<TokenAmount>,
+) -> Result<(AutoShareDetails, Vec<TokenDistribution>), Error> {
+    if get_paused_status(env) {
+        return Err(Error::ContractPaused);
+    }
+
+    for amount in amounts.iter() {
+        if amount.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        if !is_token_supported(env.clone(), amount.token) {
+            return Err(Error::UnsupportedToken);
+        }
+    }
+
+    let details = load_distributable_group(env, id)?;
+    let splits = split_amounts(env, id, &details, amounts)?;
+    Ok((details, splits))
+}
+
+/// Splits each token of a distribution. Fixed amounts are paid from the token
+/// they are set in, so every such token must be part of the distribution.
+fn split_amounts(
+    env: &Env,
+    id: &BytesN<32>,
+    details: &AutoShareDetails,
+    amounts: &Vec<TokenAmount>,
+) -> Result<Vec<TokenDistribution>, Error> {
+    for member in details.members.iter() {
+        if let Some(fixed_token) = member.fixed_token {
+            if !amounts.iter().any(|amount| amount.token == fixed_token) {
+                return Err(Error::FixedAmountTokenMismatch);
+            }
+        }
+    }
+
+    let mut splits: Vec<TokenDistribution> = Vec::new(env);
+    for amount in amounts.iter() {
+        splits.push_back(split_distribution(
+            env,
+            id,
+            details,
+            &amount.token,
+            amount.amount,
+        )?);
+    }
+    Ok(splits)
+}
+
+/// Takes the protocol fee from `amount` of `token` and splits the rest by the
+/// group's tranches, or by its members when it has none. Tranches are walked in
+/// order from the amount the group has received so far, so one payment can be
+/// split across a cap.
+fn split_distribution(
+    env: &Env,
+    id: &BytesN<32>,
+    details: &AutoShareDetails,
+    token: &Address,
+    amount: i128,
+) -> Result<TokenDistribution, Error> {
+    let fee_amount = distribution_fee(env, id, amount);
+    let net_amount = amount - fee_amount;
+    let tranches = read_tranches(env, id);
+    if tranches.is_empty() {
+        return Ok(TokenDistribution {
+            token: token.clone(),
+            total_amount: amount,
+            fee_amount,
+            member_amounts: compute_shares(env, &details.members, token, net_amount)?,
+            tranches: Vec::new(env),
+        });
+    }
+
+    let mut received = read_received(env, id, token);
+    let mut remaining = net_amount;
+    let mut member_amounts: Vec<MemberAmount> = Vec::new(env);
+    let mut applied: Vec<u32> = Vec::new(env);
+    for (idx, tranche) in tranches.iter().enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        let portion = match tranche.cap {
+            Some(cap) if received >= cap => continue,
+            Some(cap) => remaining.min(cap - received),
+            None => remaining,
+        };
+        for share in compute_shares(env, &tranche.members, token, portion)?.iter() {
+            add_member_amount(&mut member_amounts, share);
+        }
+        applied.push_back(idx as u32);
+        received += portion;
+        remaining -= portion;
+    }
+
+    Ok(TokenDistribution {
+        token: token.clone(),
+        total_amount: amount,
+        fee_amount,
+        member_amounts,
+        tranches: applied,
+    })
+}
+
+/// Adds `share` to the member's entry, so a member paid by several tranches
+/// appears once.
+fn add_member_amount(member_amounts: &mut Vec<MemberAmount>, share: MemberAmount) {
+    for (idx, existing) in member_amounts.iter().enumerate() {
+        if existing.address == share.address {
+            member_amounts.set(
+                idx as u32,
+                MemberAmount {
+                    address: share.address,
+                    amount: existing.amount + share.amount,
+                },
+            );
+            return;
+        }
+    }
+    member_amounts.push_back(share);
+}
+
+/// Loads a group that can currently receive a distribution: it must exist, be
+/// active, hold a valid member split (or tranches) and, unless it pays a
+/// percentage fee, have usages left or an active time subscription.
+fn load_distributable_group(env: &Env, id: &BytesN<32>) -> Result<AutoShareDetails, Error> {
+    let key = DataKey::AutoShare(id.clone());
+    let details: AutoShareDetails = read_details(env, &key).ok_or(Error::NotFound)?;
+    bump_persistent(env, &key);
+
+    if !details.is_active {
+        return Err(Error::GroupInactive);
+    }
+
+    if details.usage_count == 0 && !pays_protocol_fee(env, id) && !has_active_subscription(env, id)
+    {
+        return Err(Error::NoUsagesRemaining);
+    }
+
+    if read_tranches(env, id).is_empty() {
+        validate_members(&details.members)?;
+    }
+    Ok(details)
+}
+
+pub fn distribute(
+    env: Env,
+    id: BytesN<32>,
+    token: Address,
+    amount: i128,
+    sender: Address,
+) -> Result<(), Error> {
+    let amounts = Vec::from_array(&env, [TokenAmount { token, amount }]);
+    distribute_multi(env, id, amounts, sender)
+}
+
+/// Distributes several tokens to the group at once, splitting each with the
+/// group's members. Spends a single usage and records one history entry that
+/// holds the shares of every token. Each token may appear once. Fixed-amount
+/// members are paid from the first token only.
+#[allow(clippy::needless_borrows_for_generic_args)]
+pub fn distribute_multi(
+    env: Env,
+    id: BytesN<32>,
+    amounts: Vec<TokenAmount>,
+    sender: Address,
+) -> Result<(), Error> {
+    sender.require_auth();
+
+    if amounts.is_empty() {
+        return Err(Error::InvalidInput);
+    }
+    let mut seen_tokens: Vec<Address> = Vec::new(&env);
+    for amount in amounts.iter() {
+        if seen_tokens.contains(&amount.token) {
+            return Err(Error::InvalidInput);
+        }
+        seen_tokens.push_back(amount.token);
     }
-    user_history.push_back(payment.clone());
-    env.storage()
-        .persistent()
-        .set(&user_history_key, &user_history);
-    bump_persistent(&env, &user_history_key);
 
-    // Add to group's payment history
-    let group_history_key = DataKey::GroupPaymentHistory(group_id);
-    let mut group_history: Vec<PaymentHistory> = env
-        .storage()
-        .persistent()
-        .get(&group_history_key)
-        .unwrap_or(Vec::new(&env));
-    if !group_history.is_empty() {
-        bump_persistent(&env, &group_history_key);
+    let (details, splits) = prepare_distribution(&env, &id, &amounts)?;
+
+    for amount in amounts.iter() {
+        token::TokenClient::new(&env, &amount.token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &amount.amount,
+        );
     }
-    group_history.push_back(payment);
-    env.storage()
-        .persistent()
-        .set(&group_history_key, &group_history);
-    bump_persistent(&env, &group_history_key);
+
+    record_group_distribution(&env, &id, details, &splits, &sender, false, None);
+    pay_shares(&env, &id, &splits, &sender, 0)
 }
 
-pub fn get_user_payment_history(env: Env, user: Address) -> Vec<PaymentHistory> {
-    let user_history_key = DataKey::UserPaymentHistory(user);
-    let result: Option<Vec<PaymentHistory>> = env.storage().persistent().get(&user_history_key);
-    if result.is_some() {
-        bump_persistent(&env, &user_history_key);
+/// Makes several distributions for one sender, taking a single transfer per
+/// token and then splitting into each group as `distribute` would. Fails as a
+/// whole if any distribution fails.
+#[allow(clippy::needless_borrows_for_generic_args)]
+pub fn distribute_batch(
+    env: Env,
+    sender: Address,
+    items: Vec<BatchItem>,
+) -> Result<BatchSummary, Error> {
+    sender.require_auth();
+
+    if items.is_empty() || items.len() > MAX_BATCH_SIZE {
+        return Err(Error::InvalidInput);
     }
-    result.unwrap_or(Vec::new(&env))
-}
 
-pub fn get_group_payment_history(env: Env, id: BytesN<32>) -> Vec<PaymentHistory> {
-    let group_history_key = DataKey::GroupPaymentHistory(id);
-    let result: Option<Vec<PaymentHistory>> = env.storage().persistent().get(&group_history_key);
-    if result.is_some() {
-        bump_persistent(&env, &group_history_key);
+    let mut totals: Vec<TokenAmount> = Vec::new(&env);
+    for item in items.iter() {
+        if item.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        add_token_amount(&mut totals, &item.token, item.amount);
     }
-    result.unwrap_or(Vec::new(&env))
+    for total in totals.iter() {
+        if !is_token_supported(env.clone(), total.token.clone()) {
+            return Err(Error::UnsupportedToken);
+        }
+        token::TokenClient::new(&env, &total.token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &total.amount,
+        );
+    }
+
+    let mut fees: Vec<TokenAmount> = Vec::new(&env);
+    for item in items.iter() {
+        let amounts = Vec::from_array(
+            &env,
+            [TokenAmount {
+                token: item.token.clone(),
+                amount: item.amount,
+            }],
+        );
+        let (details, splits) = prepare_distribution(&env, &item.id, &amounts)?;
+        let fee_amount = splits.get_unchecked(0).fee_amount;
+        if fee_amount > 0 {
+            add_token_amount(&mut fees, &item.token, fee_amount);
+        }
+        record_group_distribution(&env, &item.id, details, &splits, &sender, false, None);
+        pay_shares(&env, &item.id, &splits, &sender, 0)?;
+    }
+
+    Ok(BatchSummary {
+        distributions: items.len(),
+        totals,
+        fees,
+    })
 }
 
-// ============================================================================
-// Distribution History
-// ============================================================================
+/// Splits a child-group member's shares of `amounts`, already held by the
+/// contract, through the child group `id`. Each level takes its own protocol fee,
+/// spends its own usage and records its own history entry.
+fn distribute_nested(
+    env: &Env,
+    id: &BytesN<32>,
+    parent: &BytesN<32>,
+    amounts: &Vec<TokenAmount>,
+    sender: &Address,
+    level: u32,
+) -> Result<(), Error> {
+    if level > MAX_NESTING_DEPTH {
+        return Err(Error::NestingTooDeep);
+    }
 
-fn record_distribution(
-    env: Env,
-    group_id: BytesN<32>,
-    sender: Address,
-    total_amount: i128,
-    token: Address,
-    member_amounts: Vec<MemberAmount>,
-    distribution_number: u32,
-) {
-    let timestamp = env.ledger().timestamp();
+    let details = load_distributable_group(env, id)?;
+    let splits = split_amounts(env, id, &details, amounts)?;
+    record_group_distribution(
+        env,
+        id,
+        details,
+        &splits,
+        sender,
+        false,
+        Some(parent.clone()),
+    );
+    pay_shares(env, id, &splits, sender, level)
+}
 
-    let distribution = DistributionHistory {
-        group_id: group_id.clone(),
-        sender: sender.clone(),
-        total_amount,
-        token: token.clone(),
-        member_amounts: member_amounts.clone(),
-        timestamp,
-        distribution_number,
-    };
+/// Returns the child group of each member that has one. Tranche members are
+/// paid directly, so a group with tranches has none.
+fn read_child_groups(env: &Env, id: &BytesN<32>) -> Map<Address, BytesN<32>> {
+    let mut children = Map::new(env);
+    if !read_tranches(env, id).is_empty() {
+        return children;
+    }
 
-    // Add to group's distribution history
-    let group_history_key = DataKey::GroupDistributionHistory(group_id);
-    let mut group_history: Vec<DistributionHistory> = env
-        .storage()
-        .persistent()
-        .get(&group_history_key)
-        .unwrap_or(Vec::new(&env));
-    group_history.push_back(distribution.clone());
-    env.storage()
-        .persistent()
-        .set(&group_history_key, &group_history);
+    let details: Option<AutoShareDetails> = read_details(env, &DataKey::AutoShare(id.clone()));
+    if let Some(details) = details {
+        for member in details.members.iter() {
+            if let Some(child) = member.group {
+                children.set(member.address, child);
+            }
+        }
+    }
+    children
+}
 
-    // Add to each member's distribution history
-    for member_amount in member_amounts.iter() {
-        let member_history_key = DataKey::MemberDistributionHistory(member_amount.address.clone());
-        let mut member_history: Vec<DistributionHistory> = env
-            .storage()
-            .persistent()
-            .get(&member_history_key)
-            .unwrap_or(Vec::new(&env));
-        member_history.push_back(distribution.clone());
-        env.storage()
-            .persistent()
-            .set(&member_history_key, &member_history);
+/// Pays out splits of funds held by the contract according to the group's
+/// distribution mode, passing the shares of child-group members down to them.
+fn pay_shares(
+    env: &Env,
+    id: &BytesN<32>,
+    splits: &Vec<TokenDistribution>,
+    sender: &Address,
+    level: u32,
+) -> Result<(), Error> {
+    let children = read_child_groups(env, id);
+    let mode = read_distribution_mode(env, id);
+    for split in splits.iter() {
+        let client = token::TokenClient::new(env, &split.token);
+        for member_amount in split.member_amounts.iter() {
+            if children.contains_key(member_amount.address.clone()) {
+                continue;
+            }
+            match mode {
+                DistributionMode::Push => client.transfer(
+                    &env.current_contract_address(),
+                    &member_amount.address,
+                    &member_amount.amount,
+                ),
+                DistributionMode::Claim => credit_claimable(
+                    env,
+                    &member_amount.address,
+                    &split.token,
+                    member_amount.amount,
+                ),
+            }
+        }
     }
+    pay_child_shares(env, id, &children, splits, sender, level)
 }
 
-pub fn get_group_distributions(env: Env, id: BytesN<32>) -> Vec<DistributionHistory> {
-    let group_history_key = DataKey::GroupDistributionHistory(id);
-    env.storage()
-        .persistent()
-        .get(&group_history_key)
-        .unwrap_or(Vec::new(&env))
+/// Splits the shares of child-group members, already held by the contract,
+/// through their child groups. A child receives all its tokens in one
+/// distribution.
+fn pay_child_shares(
+    env: &Env,
+    id: &BytesN<32>,
+    children: &Map<Address, BytesN<32>>,
+    splits: &Vec<TokenDistribution>,
+    sender: &Address,
+    level: u32,
+) -> Result<(), Error> {
+    for (member, child) in children.iter() {
+        let mut amounts: Vec<TokenAmount> = Vec::new(env);
+        for split in splits.iter() {
+            for member_amount in split.member_amounts.iter() {
+                if member_amount.address == member {
+                    amounts.push_back(TokenAmount {
+                        token: split.token.clone(),
+                        amount: member_amount.amount,
+                    });
+                }
+            }
+        }
+        if !amounts.is_empty() {
+            distribute_nested(env, &child, id, &amounts, sender, level + 1)?;
+        }
+    }
+    Ok(())
 }
 
-pub fn get_member_distributions(env: Env, member: Address) -> Vec<DistributionHistory> {
-    let member_history_key = DataKey::MemberDistributionHistory(member);
-    env.storage()
-        .persistent()
-        .get(&member_history_key)
-        .unwrap_or(Vec::new(&env))
+/// Does a group's accounting for one distribution: credits the fees, adds to the
+/// amounts received, records the history entry, spends a usage and publishes a
+/// `Distribution` event per token.
+fn record_group_distribution(
+    env: &Env,
+    id: &BytesN<32>,
+    mut details: AutoShareDetails,
+    splits: &Vec<TokenDistribution>,
+    sender: &Address,
+    is_deposit: bool,
+    parent_group: Option<BytesN<32>>,
+) {
+    // Received amounts only matter for tranche caps
+    let has_tranches = !read_tranches(env, id).is_empty();
+    for split in splits.iter() {
+        if split.fee_amount > 0 {
+            credit_fee_revenue(env, &split.token, split.fee_amount);
+        }
+        if has_tranches {
+            add_received(env, id, &split.token, split.total_amount - split.fee_amount);
+        }
+    }
+
+    let mut other_tokens = splits.clone();
+    let first = other_tokens.pop_front_unchecked();
+    // Numbered by the group's history rather than its usages, which time plans
+    // and percentage fees leave unspent
+    let distribution_number =
+        history_len::<DistributionHistory>(env, &History::GroupDistributions(id.clone()));
+    record_distribution(
+        env.clone(),
+        DistributionHistory {
+            group_id: id.clone(),
+            sender: sender.clone(),
+            total_amount: first.total_amount,
+            token: first.token,
+            member_amounts: first.member_amounts,
+            timestamp: env.ledger().timestamp(),
+            distribution_number,
+            is_deposit,
+            fee_amount: first.fee_amount,
+            tranches: first.tranches,
+            parent_group,
+            other_tokens,
+        },
+    );
+
+    let key = DataKey::AutoShare(id.clone());
+    spend_usage(env, id, &mut details);
+    env.storage().persistent().set(&key, &details);
+    bump_persistent(env, &key);
+
+    for split in splits.iter() {
+        Distribution {
+            id: id.clone(),
+            token: split.token,
+            sender: sender.clone(),
+            amount: split.total_amount,
+        }
+        .publish(env);
+    }
 }
 
-// ============================================================================
-// Usage Tracking
-// ============================================================================
+/// Pays the members with a fixed amount in `token` first and splits the rest
+/// by the other members' basis points. The last percentage member receives the
+/// remainder so that the full amount is always distributed. Fails with
+/// `AmountBelowFixedShares` when `amount` does not cover the fixed amounts.
+/// Members with a fixed amount in another token get nothing.
+fn compute_shares(
+    env: &Env,
+    members: &Vec<GroupMember>,
+    token: &Address,
+    amount: i128,
+) -> Result<Vec<MemberAmount>, Error> {
+    let mut fixed_total: i128 = 0;
+    let mut last_percentage_idx = None;
+    for (idx, member) in members.iter().enumerate() {
+        if member.fixed_amount > 0 {
+            if member.fixed_token.as_ref() == Some(token) {
+                fixed_total += member.fixed_amount;
+            }
+        } else {
+            last_percentage_idx = Some(idx);
+        }
+    }
+    if amount < fixed_total {
+        return Err(Error::AmountBelowFixedShares);
+    }
 
-pub fn get_remaining_usages(env: Env, id: BytesN<32>) -> Result<u32, Error> {
-    let key = DataKey::AutoShare(id);
-    let details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
-    bump_persistent(&env, &key);
-    Ok(details.usage_count)
+    let remainder = amount - fixed_total;
+    let mut distributed: i128 = 0;
+    let mut member_amounts: Vec<MemberAmount> = Vec::new(env);
+    for (idx, member) in members.iter().enumerate() {
+        let share = if member.fixed_amount > 0 {
+            if member.fixed_token.as_ref() == Some(token) {
+                member.fixed_amount
+            } else {
+                0
+            }
+        } else if Some(idx) != last_percentage_idx {
+            let share = (remainder * (member.percentage as i128)) / (TOTAL_BASIS_POINTS as i128);
+            distributed += share;
+            share
+        } else {
+            remainder - distributed
+        };
+        if share > 0 {
+            member_amounts.push_back(MemberAmount {
+                address: member.address.clone(),
+                amount: share,
+            });
+        }
+    }
+    Ok(member_amounts)
 }
 
-pub fn get_total_usages_paid(env: Env, id: BytesN<32>) -> Result<u32, Error> {
-    let key = DataKey::AutoShare(id);
-    let details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
-    bump_persistent(&env, &key);
-    Ok(details.total_usages_paid)
+fn validate_members(members: &Vec<GroupMember>) -> Result<(), Error> {
+    if members.is_empty() {
+        return Err(Error::EmptyMembers);
+    }
+    let env = members.env();
+    let mut total_percentage: u32 = 0;
+    let mut seen_addresses = Vec::new(env);
+
+    for member in members.iter() {
+        // A member is paid either a fixed amount or a percentage, not both
+        if member.fixed_amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if member.fixed_amount > 0 && member.percentage > 0 {
+            return Err(Error::InvalidInput);
+        }
+        // A fixed amount names the token it is paid in
+        if (member.fixed_amount > 0) != member.fixed_token.is_some() {
+            return Err(Error::InvalidInput);
+        }
+        total_percentage = total_percentage
+            .checked_add(member.percentage)
+            .ok_or(Error::InvalidTotalPercentage)?;
+        for seen in seen_addresses.iter() {
+            if seen == member.address {
+                return Err(Error::DuplicateMember);
+            }
+        }
+        seen_addresses.push_back(member.address.clone());
+    }
+
+    if total_percentage != TOTAL_BASIS_POINTS {
+        return Err(Error::InvalidTotalPercentage);
+    }
+    Ok(())
 }
 
-#[cfg(test)]
-#[allow(dead_code)]
-pub fn reduce_usage(env: Env, id: BytesN<32>) -> Result<(), Error> {
-    let key = DataKey::AutoShare(id);
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
-    bump_persistent(&env, &key);
+/// Checks that the child groups referenced by `members` exist, do not lead back
+/// to group `id` and stay within `MAX_NESTING_DEPTH` levels below it.
+fn validate_child_groups(
+    env: &Env,
+    id: &BytesN<32>,
+    members: &Vec<GroupMember>,
+) -> Result<(), Error> {
+    for member in members.iter() {
+        if let Some(child) = member.group {
+            check_nesting(env, id, &child, 1)?;
+        }
+    }
+    Ok(())
+}
 
-    if details.usage_count == 0 {
-        return Err(Error::NoUsagesRemaining);
+/// Walks the child groups below `group`, which sits `level` levels below `root`.
+fn check_nesting(
+    env: &Env,
+    root: &BytesN<32>,
+    group: &BytesN<32>,
+    level: u32,
+) -> Result<(), Error> {
+    if group == root {
+        return Err(Error::NestedGroupCycle);
+    }
+    if level > MAX_NESTING_DEPTH {
+        return Err(Error::NestingTooDeep);
     }
 
-    details.usage_count -= 1;
-    env.storage().persistent().set(&key, &details);
-    bump_persistent(&env, &key);
+    let details: AutoShareDetails =
+        read_details(env, &DataKey::AutoShare(group.clone())).ok_or(Error::NotFound)?;
+    for member in details.members.iter() {
+        if let Some(child) = member.group {
+            check_nesting(env, root, &child, level + 1)?;
+        }
+    }
     Ok(())
 }
 
 // ============================================================================
-// Group Activation Management
+// Tranches
 // ============================================================================
 
-pub fn update_members(
+/// Replaces the group's waterfall. Each tranche applies until the group has
+/// received its cumulative `cap` in a token while it has tranches; caps must
+/// increase and only the last tranche is uncapped. Tranche members take percentages only and are paid
+/// directly. An empty list returns the group to its regular member split. Only
+/// the creator can change it.
+pub fn set_tranches(
     env: Env,
     id: BytesN<32>,
     caller: Address,
-    new_members: Vec<GroupMember>,
+    tranches: Vec<Tranche>,
 ) -> Result<(), Error> {
     caller.require_auth();
 
@@ -815,63 +3582,143 @@ pub fn update_members(
     }
 
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
     bump_persistent(&env, &key);
 
     if details.creator != caller {
         return Err(Error::Unauthorized);
     }
 
-    if !details.is_active {
-        return Err(Error::GroupInactive);
-    }
+    validate_tranches(&tranches)?;
 
-    // Validate new members
-    if new_members.is_empty() {
-        return Err(Error::EmptyMembers);
+    update_member_index(
+        &env,
+        &id,
+        &group_payees(&env, &details.members, &read_tranches(&env, &id)),
+        &group_payees(&env, &details.members, &tranches),
+    );
+
+    let tranches_key = DataKey::GroupTranches(id.clone());
+    if tranches.is_empty() {
+        env.storage().persistent().remove(&tranches_key);
+    } else {
+        env.storage().persistent().set(&tranches_key, &tranches);
+        bump_persistent(&env, &tranches_key);
     }
 
-    let mut total_percentage: u32 = 0;
-    let mut seen_addresses = Vec::new(&env);
+    AutoshareUpdated {
+        id,
+        updater: caller,
+    }
+    .publish(&env);
+    Ok(())
+}
 
-    for member in new_members.iter() {
-        total_percentage += member.percentage;
+fn validate_tranches(tranches: &Vec<Tranche>) -> Result<(), Error> {
+    let mut previous_cap: i128 = 0;
+    for (idx, tranche) in tranches.iter().enumerate() {
+        let is_last = idx + 1 == tranches.len() as usize;
+        match tranche.cap {
+            Some(cap) if !is_last && cap > previous_cap => previous_cap = cap,
+            None if is_last => {}
+            _ => return Err(Error::InvalidInput),
+        }
 
-        for seen in seen_addresses.iter() {
-            if seen == member.address {
-                return Err(Error::DuplicateMember);
+        validate_members(&tranche.members)?;
+        for member in tranche.members.iter() {
+            if member.fixed_amount > 0 || member.group.is_some() {
+                return Err(Error::InvalidInput);
             }
         }
-        seen_addresses.push_back(member.address.clone());
     }
+    Ok(())
+}
 
-    if total_percentage != 100 {
-        return Err(Error::InvalidTotalPercentage);
+pub fn get_tranches(env: Env, id: BytesN<32>) -> Result<Vec<Tranche>, Error> {
+    let key = DataKey::AutoShare(id.clone());
+    if !env.storage().persistent().has(&key) {
+        return Err(Error::NotFound);
     }
-
-    // Update members in details
-    details.members = new_members.clone();
-    env.storage().persistent().set(&key, &details);
     bump_persistent(&env, &key);
 
-    // Also update the GroupMembers storage
-    let members_key = DataKey::GroupMembers(id.clone());
-    env.storage().persistent().set(&members_key, &new_members);
-    bump_persistent(&env, &members_key);
+    Ok(read_tranches(&env, &id))
+}
 
-    AutoshareUpdated {
-        id: id.clone(),
-        updater: caller,
+fn read_tranches(env: &Env, id: &BytesN<32>) -> Vec<Tranche> {
+    let tranches_key = DataKey::GroupTranches(id.clone());
+    let result: Option<Vec<Tranche>> = env.storage().persistent().get(&tranches_key);
+    if result.is_some() {
+        bump_persistent(env, &tranches_key);
     }
-    .publish(&env);
-    Ok(())
+    result.unwrap_or(Vec::new(env))
+}
+
+/// Returns the total amount of `token` the group has split among its members
+/// while it had tranches, after protocol fees. Tranche caps are measured
+/// against it.
+pub fn get_cumulative_received(env: Env, id: BytesN<32>, token: Address) -> i128 {
+    read_received(&env, &id, &token)
+}
+
+fn read_received_totals(env: &Env, id: &BytesN<32>) -> Vec<TokenAmount> {
+    let received_key = DataKey::GroupReceived(id.clone());
+    let result: Option<Vec<TokenAmount>> = env.storage().persistent().get(&received_key);
+    if result.is_some() {
+        bump_persistent(env, &received_key);
+    }
+    result.unwrap_or(Vec::new(env))
+}
+
+fn read_received(env: &Env, id: &BytesN<32>, token: &Address) -> i128 {
+    for total in read_received_totals(env, id).iter() {
+        if total.token == *token {
+            return total.amount;
+        }
+    }
+    0
+}
+
+fn add_received(env: &Env, id: &BytesN<32>, token: &Address, amount: i128) {
+    let mut totals = read_received_totals(env, id);
+    add_token_amount(&mut totals, token, amount);
+
+    let received_key = DataKey::GroupReceived(id.clone());
+    env.storage().persistent().set(&received_key, &totals);
+    bump_persistent(env, &received_key);
 }
 
-pub fn deactivate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+/// Adds `amount` to the entry for `token`, appending one if there is none.
+fn add_token_amount(totals: &mut Vec<TokenAmount>, token: &Address, amount: i128) {
+    for (idx, total) in totals.iter().enumerate() {
+        if total.token == *token {
+            totals.set(
+                idx as u32,
+                TokenAmount {
+                    token: total.token,
+                    amount: total.amount + amount,
+                },
+            );
+            return;
+        }
+    }
+    totals.push_back(TokenAmount {
+        token: token.clone(),
+        amount,
+    });
+}
+
+// ============================================================================
+// Claimable Balances
+// ============================================================================
+
+/// Sets whether distributions of a group are pushed to members immediately or
+/// credited as claimable balances. Only the creator can change it.
+pub fn set_distribution_mode(
+    env: Env,
+    id: BytesN<32>,
+    caller: Address,
+    mode: DistributionMode,
+) -> Result<(), Error> {
     caller.require_auth();
 
     if get_paused_status(&env) {
@@ -879,330 +3726,322 @@ pub fn deactivate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(),
     }
 
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
     bump_persistent(&env, &key);
 
     if details.creator != caller {
         return Err(Error::Unauthorized);
     }
 
-    if !details.is_active {
-        return Err(Error::GroupAlreadyInactive);
-    }
-
-    details.is_active = false;
-    env.storage().persistent().set(&key, &details);
-    bump_persistent(&env, &key);
+    let mode_key = DataKey::GroupDistributionMode(id.clone());
+    env.storage().persistent().set(&mode_key, &mode);
+    bump_persistent(&env, &mode_key);
 
-    GroupDeactivated {
-        id: id.clone(),
-        creator: caller,
+    AutoshareUpdated {
+        id,
+        updater: caller,
     }
     .publish(&env);
     Ok(())
 }
 
-pub fn activate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
-    caller.require_auth();
-
-    if get_paused_status(&env) {
-        return Err(Error::ContractPaused);
-    }
-
+pub fn get_distribution_mode(env: Env, id: BytesN<32>) -> Result<DistributionMode, Error> {
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    if !env.storage().persistent().has(&key) {
+        return Err(Error::NotFound);
+    }
     bump_persistent(&env, &key);
 
-    if details.creator != caller {
-        return Err(Error::Unauthorized);
-    }
+    Ok(read_distribution_mode(&env, &id))
+}
 
-    if details.is_active {
-        return Err(Error::GroupAlreadyActive);
+fn read_distribution_mode(env: &Env, id: &BytesN<32>) -> DistributionMode {
+    let mode_key = DataKey::GroupDistributionMode(id.clone());
+    let result: Option<DistributionMode> = env.storage().persistent().get(&mode_key);
+    if result.is_some() {
+        bump_persistent(env, &mode_key);
     }
+    result.unwrap_or(DistributionMode::Push)
+}
 
-    details.is_active = true;
-    env.storage().persistent().set(&key, &details);
-    bump_persistent(&env, &key);
+fn credit_claimable(env: &Env, member: &Address, token: &Address, amount: i128) {
+    let claim_key = DataKey::Claimable(member.clone(), token.clone());
+    let balance: i128 = env.storage().persistent().get(&claim_key).unwrap_or(0);
+    env.storage()
+        .persistent()
+        .set(&claim_key, &(balance + amount));
+    bump_persistent(env, &claim_key);
+    add_token_liabilities(env, token, amount);
 
-    GroupActivated {
-        id: id.clone(),
-        creator: caller,
+    if balance == 0 {
+        let tokens_key = DataKey::ClaimableTokens(member.clone());
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&tokens_key)
+            .unwrap_or(Vec::new(env));
+        if !tokens.contains(token) {
+            tokens.push_back(token.clone());
+            env.storage().persistent().set(&tokens_key, &tokens);
+        }
+        bump_persistent(env, &tokens_key);
     }
-    .publish(&env);
-    Ok(())
 }
 
-pub fn is_group_active(env: Env, id: BytesN<32>) -> Result<bool, Error> {
-    let key = DataKey::AutoShare(id);
-    let details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
-    bump_persistent(&env, &key);
-    Ok(details.is_active)
+pub fn get_claimable(env: Env, member: Address, token: Address) -> i128 {
+    let claim_key = DataKey::Claimable(member, token);
+    let result: Option<i128> = env.storage().persistent().get(&claim_key);
+    if result.is_some() {
+        bump_persistent(&env, &claim_key);
+    }
+    result.unwrap_or(0)
 }
 
-// ============================================================================
-// Group Deletion
-// ============================================================================
+/// Pays out a member's claimable balance of one token and clears it.
+fn take_claimable(env: &Env, member: &Address, token: &Address) -> i128 {
+    let claim_key = DataKey::Claimable(member.clone(), token.clone());
+    let amount: i128 = env.storage().persistent().get(&claim_key).unwrap_or(0);
+    if amount <= 0 {
+        return 0;
+    }
+    env.storage().persistent().remove(&claim_key);
+    add_token_liabilities(env, token, -amount);
 
-/// Permanently deletes a group from the contract.
-/// Requirements:
-/// 1. Caller must be the group creator or admin
-/// 2. Group must be deactivated
-/// 3. Group must have 0 remaining usages (or they are forfeited)
-/// 4. Removes group from AllGroups list
-/// 5. Removes AutoShare(id) entry
-/// 6. Removes GroupMembers(id) entry
-/// 7. Archives payment history before deletion (keeps it for audit trail)
-/// 8. Emits GroupDeleted event
-pub fn delete_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
-    caller.require_auth();
+    let client = token::TokenClient::new(env, token);
+    client.transfer(&env.current_contract_address(), member, &amount);
+
+    Claimed {
+        member: member.clone(),
+        token: token.clone(),
+        amount,
+    }
+    .publish(env);
+    amount
+}
+
+pub fn claim(env: Env, member: Address, token: Address) -> Result<i128, Error> {
+    member.require_auth();
 
     if get_paused_status(&env) {
         return Err(Error::ContractPaused);
     }
 
-    // Step 1: Verify group exists
-    let key = DataKey::AutoShare(id.clone());
-    let details: AutoShareDetails = env
+    let amount = take_claimable(&env, &member, &token);
+    if amount == 0 {
+        return Err(Error::NothingToClaim);
+    }
+
+    let tokens_key = DataKey::ClaimableTokens(member.clone());
+    let tokens: Vec<Address> = env
         .storage()
         .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
-    // We don't bump here if we are removing it, but we might return early if deactivated check fails
-    // However, the requirement says bump on every read.
-    bump_persistent(&env, &key);
-
-    // Step 2: Verify caller is creator or admin
-    let admin_result = get_admin(env.clone());
-    let is_admin = admin_result.is_ok() && admin_result.unwrap() == caller;
-    let is_creator = details.creator == caller;
-
-    if !is_creator && !is_admin {
-        return Err(Error::Unauthorized);
+        .get(&tokens_key)
+        .unwrap_or(Vec::new(&env));
+    if let Some(idx) = tokens.first_index_of(&token) {
+        let mut remaining = tokens.clone();
+        remaining.remove(idx);
+        if remaining.is_empty() {
+            env.storage().persistent().remove(&tokens_key);
+        } else {
+            env.storage().persistent().set(&tokens_key, &remaining);
+            bump_persistent(&env, &tokens_key);
+        }
     }
 
-    // Step 3: Check group is already deactivated
-    if details.is_active {
-        return Err(Error::GroupNotDeactivated);
-    }
+    Ok(amount)
+}
 
-    // Step 4: Check group has 0 remaining usages (or warn about forfeiture)
-    // We allow deletion even with remaining usages, but this is a design choice
-    // In production, you might want to enforce zero usages or handle refunds
-    if details.usage_count > 0 {
-        // Option 1: Strict enforcement - uncomment to require zero usages
-        // return Err(Error::GroupHasRemainingUsages);
+pub fn claim_all(env: Env, member: Address) -> Result<Vec<TokenAmount>, Error> {
+    member.require_auth();
 
-        // Option 2: Allow deletion with forfeiture (current implementation)
-        // The remaining usages are simply forfeited
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
     }
 
-    // Step 5: Remove the group from AllGroups list
-    let all_groups_key = DataKey::AllGroups;
-    let group_ids: Vec<BytesN<32>> = env
+    let tokens_key = DataKey::ClaimableTokens(member.clone());
+    let tokens: Vec<Address> = env
         .storage()
         .persistent()
-        .get(&all_groups_key)
+        .get(&tokens_key)
         .unwrap_or(Vec::new(&env));
-    if !group_ids.is_empty() {
-        bump_persistent(&env, &all_groups_key);
-    }
 
-    let mut new_group_ids: Vec<BytesN<32>> = Vec::new(&env);
-    for group_id in group_ids.iter() {
-        if group_id != id {
-            new_group_ids.push_back(group_id);
+    let mut claimed: Vec<TokenAmount> = Vec::new(&env);
+    for token in tokens.iter() {
+        let amount = take_claimable(&env, &member, &token);
+        if amount > 0 {
+            claimed.push_back(TokenAmount { token, amount });
         }
     }
-    env.storage()
-        .persistent()
-        .set(&all_groups_key, &new_group_ids);
-    bump_persistent(&env, &all_groups_key);
-
-    // Step 6: Remove the AutoShare(id) entry
-    env.storage().persistent().remove(&key);
-
-    // Step 7: Remove GroupMembers(id) entry
-    let members_key = DataKey::GroupMembers(id.clone());
-    env.storage().persistent().remove(&members_key);
-
-    // Step 8: Archive payment history (we keep it for audit trail)
-    // Payment history is intentionally NOT deleted to maintain financial records
-    // This is a best practice for compliance and auditing purposes
-    // The entries remain in:
-    // - DataKey::UserPaymentHistory(Address)
-    // - DataKey::GroupPaymentHistory(BytesN<32>)
+    env.storage().persistent().remove(&tokens_key);
 
-    // Step 9: Emit deletion event
-    GroupDeleted {
-        deleter: caller,
-        id: id.clone(),
+    if claimed.is_empty() {
+        return Err(Error::NothingToClaim);
     }
-    .publish(&env);
-
-    Ok(())
+    Ok(claimed)
 }
 
 // ============================================================================
-// Contract Balance & Withdrawal
+// Group Splitter Contracts
 // ============================================================================
 
-pub fn get_contract_balance(env: Env, token: Address) -> i128 {
-    let client = token::TokenClient::new(&env, &token);
-    client.balance(&env.current_contract_address())
-}
-
-pub fn withdraw(
+pub fn set_splitter_wasm_hash(
     env: Env,
     admin: Address,
-    token: Address,
-    amount: i128,
-    recipient: Address,
+    wasm_hash: BytesN<32>,
 ) -> Result<(), Error> {
     admin.require_auth();
     require_admin(&env, &admin)?;
 
-    if amount <= 0 {
-        return Err(Error::InvalidAmount);
-    }
+    apply_splitter_wasm_hash(&env, wasm_hash)
+}
 
-    let contract_balance = get_contract_balance(env.clone(), token.clone());
-    if contract_balance < amount {
-        return Err(Error::InsufficientContractBalance);
+fn apply_splitter_wasm_hash(env: &Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+    let hash_key = DataKey::SplitterWasmHash;
+    env.storage().persistent().set(&hash_key, &wasm_hash);
+    bump_persistent(env, &hash_key);
+    Ok(())
+}
+
+fn read_splitter_wasm_hash(env: &Env) -> Option<BytesN<32>> {
+    let hash_key = DataKey::SplitterWasmHash;
+    let result: Option<BytesN<32>> = env.storage().persistent().get(&hash_key);
+    if result.is_some() {
+        bump_persistent(env, &hash_key);
     }
+    result
+}
 
-    let client = token::TokenClient::new(&env, &token);
-    client.transfer(&env.current_contract_address(), &recipient, &amount);
+pub fn get_splitter_wasm_hash(env: Env) -> Result<BytesN<32>, Error> {
+    read_splitter_wasm_hash(&env).ok_or(Error::SplitterNotConfigured)
+}
 
-    Withdrawal {
-        token,
-        amount,
-        recipient,
+/// Deploys a splitter for the group with the group id as salt and records it in the registry.
+fn deploy_splitter(env: &Env, id: &BytesN<32>, wasm_hash: BytesN<32>) -> Address {
+    let splitter = env
+        .deployer()
+        .with_current_contract(id.clone())
+        .deploy_v2(wasm_hash, (env.current_contract_address(), id.clone()));
+
+    let splitter_key = DataKey::GroupSplitter(id.clone());
+    env.storage().persistent().set(&splitter_key, &splitter);
+    bump_persistent(env, &splitter_key);
+
+    SplitterDeployed {
+        id: id.clone(),
+        splitter: splitter.clone(),
     }
-    .publish(&env);
-    Ok(())
+    .publish(env);
+    splitter
 }
 
-#[allow(clippy::needless_borrows_for_generic_args)]
-pub fn distribute(
-    env: Env,
-    id: BytesN<32>,
-    token: Address,
-    amount: i128,
-    sender: Address,
-) -> Result<(), Error> {
-    sender.require_auth();
+/// Deploys a splitter for a group created before a splitter WASM was configured.
+/// Only the creator can call.
+pub fn deploy_group_splitter(env: Env, id: BytesN<32>, caller: Address) -> Result<Address, Error> {
+    caller.require_auth();
 
     if get_paused_status(&env) {
         return Err(Error::ContractPaused);
     }
 
-    if amount <= 0 {
-        return Err(Error::InvalidAmount);
-    }
-
-    if !is_token_supported(env.clone(), token.clone()) {
-        return Err(Error::UnsupportedToken);
-    }
-
     let key = DataKey::AutoShare(id.clone());
-    let mut details: AutoShareDetails = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .ok_or(Error::NotFound)?;
+    let details: AutoShareDetails = read_details(&env, &key).ok_or(Error::NotFound)?;
     bump_persistent(&env, &key);
 
-    if !details.is_active {
-        return Err(Error::GroupInactive);
+    if details.creator != caller {
+        return Err(Error::Unauthorized);
     }
 
-    if details.usage_count == 0 {
-        return Err(Error::NoUsagesRemaining);
+    let splitter_key = DataKey::GroupSplitter(id.clone());
+    if env.storage().persistent().has(&splitter_key) {
+        bump_persistent(&env, &splitter_key);
+        return Err(Error::AlreadyExists);
     }
 
-    validate_members(&details.members)?;
-
-    let client = token::TokenClient::new(&env, &token);
-    client.transfer(&sender, &env.current_contract_address(), &amount);
+    let wasm_hash = read_splitter_wasm_hash(&env).ok_or(Error::SplitterNotConfigured)?;
+    Ok(deploy_splitter(&env, &id, wasm_hash))
+}
 
-    let mut distributed: i128 = 0;
-    let members_len = details.members.len() as usize;
-    let mut member_amounts: Vec<MemberAmount> = Vec::new(&env);
-    for (idx, member) in details.members.iter().enumerate() {
-        let share = if idx + 1 < members_len {
-            (amount * (member.percentage as i128)) / 100
-        } else {
-            amount - distributed
-        };
-        if share > 0 {
-            client.transfer(&env.current_contract_address(), &member.address, &share);
-            distributed += share;
-            member_amounts.push_back(MemberAmount {
-                address: member.address.clone(),
-                amount: share,
-            });
-        }
+pub fn get_group_splitter(env: Env, id: BytesN<32>) -> Result<Address, Error> {
+    let splitter_key = DataKey::GroupSplitter(id);
+    let result: Option<Address> = env.storage().persistent().get(&splitter_key);
+    if result.is_some() {
+        bump_persistent(&env, &splitter_key);
     }
+    result.ok_or(Error::NotFound)
+}
 
-    let distribution_number = details.total_usages_paid - details.usage_count;
-    record_distribution(
-        env.clone(),
-        id.clone(),
-        sender.clone(),
-        amount,
-        token.clone(),
-        member_amounts,
-        distribution_number,
-    );
+/// Returns the balance of `token` waiting in the group's splitter to be settled.
+pub fn get_unsettled_balance(env: Env, id: BytesN<32>, token: Address) -> Result<i128, Error> {
+    let splitter = get_group_splitter(env.clone(), id)?;
+    Ok(token::TokenClient::new(&env, &token).balance(&splitter))
+}
 
-    details.usage_count -= 1;
-    env.storage().persistent().set(&key, &details);
-    bump_persistent(&env, &key);
+/// Splits the whole balance of `token` received by the group's splitter among the
+/// group's members. Anyone can call. Uses the same share calculation, distribution
+/// mode and usage accounting as `distribute`, and records the split as a deposit.
+/// Deposits sit in the splitter until settled, so they never mix with the fee
+/// revenue held by this contract.
+pub fn settle(env: Env, id: BytesN<32>, token: Address) -> Result<i128, Error> {
+    if get_paused_status(&env) {
+        return Err(Error::ContractPaused);
+    }
 
-    Distribution {
-        id,
-        token,
-        sender,
-        amount,
+    if !is_token_supported(env.clone(), token.clone()) {
+        return Err(Error::UnsupportedToken);
     }
-    .publish(&env);
 
-    Ok(())
-}
+    let details = load_distributable_group(&env, &id)?;
 
-fn validate_members(members: &Vec<GroupMember>) -> Result<(), Error> {
-    if members.is_empty() {
-        return Err(Error::EmptyMembers);
+    let splitter = get_group_splitter(env.clone(), id.clone())?;
+    let amount = token::TokenClient::new(&env, &token).balance(&splitter);
+    if amount <= 0 {
+        return Err(Error::NothingToSplit);
     }
-    let env = members.env();
-    let mut total_percentage: u32 = 0;
-    let mut seen_addresses = Vec::new(env);
 
-    for member in members.iter() {
-        total_percentage += member.percentage;
-        for seen in seen_addresses.iter() {
-            if seen == member.address {
-                return Err(Error::DuplicateMember);
+    let amounts = Vec::from_array(
+        &env,
+        [TokenAmount {
+            token: token.clone(),
+            amount,
+        }],
+    );
+    let splits = split_amounts(&env, &id, &details, &amounts)?;
+    let split = splits.get_unchecked(0);
+    record_group_distribution(&env, &id, details, &splits, &splitter, true, None);
+
+    let splitter_client = GroupSplitterClient::new(&env, &splitter);
+    match read_distribution_mode(&env, &id) {
+        DistributionMode::Push => {
+            // The fee and the shares of child groups are moved to this contract
+            let mut payouts: Vec<MemberAmount> = Vec::new(&env);
+            let children = read_child_groups(&env, &id);
+            let mut held = split.fee_amount;
+            for member_amount in split.member_amounts.iter() {
+                if children.contains_key(member_amount.address.clone()) {
+                    held += member_amount.amount;
+                } else {
+                    payouts.push_back(member_amount);
+                }
+            }
+            if held > 0 {
+                payouts.push_back(MemberAmount {
+                    address: env.current_contract_address(),
+                    amount: held,
+                });
             }
+            splitter_client.payout(&token, &payouts);
+            pay_child_shares(&env, &id, &children, &splits, &splitter, 0)?;
+        }
+        DistributionMode::Claim => {
+            let mut to_contract: Vec<MemberAmount> = Vec::new(&env);
+            to_contract.push_back(MemberAmount {
+                address: env.current_contract_address(),
+                amount,
+            });
+            splitter_client.payout(&token, &to_contract);
+            pay_shares(&env, &id, &splits, &splitter, 0)?;
         }
-        seen_addresses.push_back(member.address.clone());
     }
 
-    if total_percentage != 100 {
-        return Err(Error::InvalidTotalPercentage);
-    }
-    Ok(())
+    Ok(amount)
 }
@@ -27,4 +27,25 @@ pub enum Error {
     InsufficientContractBalance = 21,
     MemberNotFound = 22,
     GroupNotDeactivated = 23,
+    NothingToClaim = 24,
+    SplitterNotConfigured = 25,
+    NothingToSplit = 26,
+    InvalidPageSize = 27,
+    NoPendingAdmin = 28,
+    AdminProposalExpired = 29,
+    AlreadyInitialized = 30,
+    CouncilModeActive = 31,
+    CouncilNotEnabled = 32,
+    ProposalAlreadyExecuted = 33,
+    TimelockActive = 34,
+    TimelockNotReady = 35,
+    InsufficientFeeRevenue = 36,
+    SubscriptionPlanNotFound = 37,
+    AmountBelowFixedShares = 38,
+    NestedGroupCycle = 39,
+    NestingTooDeep = 40,
+    ProtocolFeeNotSet = 41,
+    UsageFeeNotSet = 42,
+    GroupReferenced = 43,
+    FixedAmountTokenMismatch = 44,
 }
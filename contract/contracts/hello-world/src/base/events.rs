@@ -1,4 +1,6 @@
-use soroban_sdk::{contractevent, Address, BytesN};
+use soroban_sdk::{contractevent, Address, BytesN, Vec};
+
+use crate::base::types::Role;
 
 #[contractevent(data_format = "single-value")]
 #[derive(Clone)]
@@ -48,6 +50,26 @@ pub struct GroupDeleted {
     pub id: BytesN<32>,
 }
 
+#[contractevent]
+#[derive(Clone)]
+pub struct SubscriptionPurchased {
+    #[topic]
+    pub id: BytesN<32>,
+    pub payer: Address,
+    pub expires_at: u64,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct UsagesRefunded {
+    #[topic]
+    pub payer: Address,
+    #[topic]
+    pub id: BytesN<32>,
+    pub token: Address,
+    pub amount: i128,
+}
+
 #[contractevent(data_format = "single-value")]
 #[derive(Clone)]
 pub struct AdminTransferred {
@@ -56,6 +78,101 @@ pub struct AdminTransferred {
     pub new_admin: Address,
 }
 
+#[contractevent]
+#[derive(Clone)]
+pub struct AdminTransferProposed {
+    #[topic]
+    pub current_admin: Address,
+    pub new_admin: Address,
+    pub expires_at: Option<u64>,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct AdminTransferCancelled {
+    #[topic]
+    pub admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct RoleGranted {
+    #[topic]
+    pub role: Role,
+    #[topic]
+    pub account: Address,
+    pub admin: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct RoleRevoked {
+    #[topic]
+    pub role: Role,
+    #[topic]
+    pub account: Address,
+    pub admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct CouncilUpdated {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct CouncilDisabled {}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct ActionProposed {
+    #[topic]
+    pub proposal_id: u32,
+    pub proposer: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct ActionApproved {
+    #[topic]
+    pub proposal_id: u32,
+    pub signer: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct ActionExecuted {
+    #[topic]
+    pub proposal_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct ActionQueued {
+    #[topic]
+    pub action_id: u32,
+    pub queued_by: Address,
+    pub eta: u64,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct QueuedActionExecuted {
+    #[topic]
+    pub action_id: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct QueuedActionCancelled {
+    #[topic]
+    pub action_id: u32,
+    pub admin: Address,
+}
+
 #[contractevent(data_format = "single-value")]
 #[derive(Clone)]
 pub struct Withdrawal {
@@ -77,3 +194,21 @@ pub struct Distribution {
     pub sender: Address,
     pub amount: i128,
 }
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct Claimed {
+    #[topic]
+    pub member: Address,
+    #[topic]
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct SplitterDeployed {
+    #[topic]
+    pub id: BytesN<32>,
+    pub splitter: Address,
+}
@@ -16,7 +16,18 @@ pub struct AutoShareDetails {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct GroupMember {
     pub address: Address,
+    /// Share of each distribution in basis points (1 = 0.01%). Members must sum to 10_000.
     pub percentage: u32,
+    /// Amount, in `fixed_token`'s smallest units, paid to the member before the
+    /// percentage split. Fixed-amount members have a percentage of 0.
+    pub fixed_amount: i128,
+    /// Token the fixed amount is paid in. Set exactly when `fixed_amount` is, and
+    /// only distributions of this token pay it.
+    pub fixed_token: Option<Address>,
+    /// Child group whose members split this member's share instead of it being
+    /// paid to `address`. The child must be able to receive a distribution, or
+    /// the parent's distribution fails.
+    pub group: Option<BytesN<32>>,
 }
 
 #[contracttype]
@@ -27,6 +38,8 @@ pub struct PaymentHistory {
     pub usages_purchased: u32,
     pub amount_paid: i128,
     pub timestamp: u64,
+    /// Volume discount applied to the purchase, in basis points.
+    pub discount_bps: u32,
 }
 
 #[contracttype]
@@ -36,6 +49,52 @@ pub struct MemberAmount {
     pub amount: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenAmount {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// One distribution of `amount` of `token` to group `id` within a batch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchItem {
+    pub id: BytesN<32>,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Result of a batch: how many distributions were made, the total taken from
+/// the sender per token and the protocol fees taken per token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchSummary {
+    pub distributions: u32,
+    pub totals: Vec<TokenAmount>,
+    pub fees: Vec<TokenAmount>,
+}
+
+/// How a group's distributions reach its members.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DistributionMode {
+    /// Shares are transferred to members inside `distribute`.
+    Push,
+    /// Shares are credited to members, who withdraw them with `claim`.
+    Claim,
+}
+
+/// How a group pays for its distributions.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeeMode {
+    /// Each distribution spends a prepaid usage or runs under a time plan.
+    Prepaid,
+    /// Each distribution pays the protocol fee, a share of the amount.
+    Percentage,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DistributionHistory {
@@ -46,4 +105,162 @@ pub struct DistributionHistory {
     pub member_amounts: Vec<MemberAmount>,
     pub timestamp: u64,
     pub distribution_number: u32,
+    /// True when the funds were deposited to the group's splitter and settled,
+    /// in which case `sender` is the splitter address.
+    pub is_deposit: bool,
+    /// Protocol fee taken from `total_amount` before the member shares.
+    pub fee_amount: i128,
+    /// Indexes of the group's tranches that applied, empty when it has none.
+    pub tranches: Vec<u32>,
+    /// Group whose member share this distribution split, when the group is
+    /// nested in another one.
+    pub parent_group: Option<BytesN<32>>,
+    /// The remaining tokens of a multi-token distribution. The fields above
+    /// describe the first token.
+    pub other_tokens: Vec<TokenDistribution>,
+}
+
+/// How one token of a distribution was split.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenDistribution {
+    pub token: Address,
+    pub total_amount: i128,
+    pub fee_amount: i128,
+    pub member_amounts: Vec<MemberAmount>,
+    pub tranches: Vec<u32>,
+}
+
+/// One stage of a group's waterfall. It splits payments among `members` until
+/// the group has received `cap` in total, after which the next tranche applies.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tranche {
+    /// Cumulative amount at which the tranche ends; `None` for the last tranche.
+    pub cap: Option<i128>,
+    pub members: Vec<GroupMember>,
+}
+
+/// A page of groups. `next_cursor` is the `start` of the following page, or
+/// `None` when there are no more entries.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupPage {
+    pub items: Vec<AutoShareDetails>,
+    pub next_cursor: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentHistoryPage {
+    pub items: Vec<PaymentHistory>,
+    pub next_cursor: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DistributionHistoryPage {
+    pub items: Vec<DistributionHistory>,
+    pub next_cursor: Option<u32>,
+}
+
+/// Usages bought in one payment, kept so unused ones can be refunded at the
+/// price and in the token they were paid with.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsageLot {
+    pub payer: Address,
+    pub token: Address,
+    pub unit_price: i128,
+    pub discount_bps: u32,
+    pub remaining: u32,
+}
+
+/// Discount applied to purchases of at least `min_usages` usages at once.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricingTier {
+    pub min_usages: u32,
+    pub discount_bps: u32,
+}
+
+/// An admin transfer waiting for the proposed admin to accept it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    /// Ledger timestamp after which the proposal can no longer be accepted.
+    pub expires_at: Option<u64>,
+}
+
+/// Operational duties the admin can delegate. The admin passes every role check.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// Can pause and unpause the contract.
+    Pauser,
+    /// Can change usage fees, pricing tiers, subscription plans and the protocol fee.
+    FeeManager,
+    /// Can add and remove supported payment tokens.
+    TokenManager,
+    /// Can withdraw funds held by the contract.
+    Treasurer,
+}
+
+/// A privileged change that a council approves before it is applied.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Token, amount, recipient.
+    Withdraw(Address, i128, Address),
+    SetUsageFee(Address, i128),
+    SetPricingTiers(Vec<PricingTier>),
+    /// Token, duration in seconds, and price (`None` removes the plan).
+    SetSubscriptionPlan(Address, u64, Option<i128>),
+    /// Protocol fee in basis points.
+    SetProtocolFee(u32),
+    AddSupportedToken(Address),
+    RemoveSupportedToken(Address),
+    Pause,
+    Unpause,
+    /// New admin and optional acceptance deadline.
+    ProposeAdmin(Address, Option<u64>),
+    CancelAdminTransfer,
+    GrantRole(Role, Address),
+    RevokeRole(Role, Address),
+    SetSplitterWasmHash(BytesN<32>),
+    /// Signers and threshold.
+    SetCouncil(Vec<Address>, u32),
+    DisableCouncil,
+    /// Timelock delay in seconds.
+    SetTimelockDelay(u64),
+    CancelQueuedAction(u32),
+}
+
+/// Signers that approve privileged actions, `threshold` of whom must agree.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Council {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CouncilProposal {
+    pub id: u32,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub executed: bool,
+}
+
+/// A timelocked action waiting for its ETA (ledger timestamp).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedAction {
+    pub id: u32,
+    pub action: AdminAction,
+    pub eta: u64,
+    pub queued_by: Address,
 }
@@ -1,6 +1,12 @@
 use soroban_sdk::{Address, BytesN, Env, String, Vec};
 
-use crate::base::types::{AutoShareDetails, DistributionHistory, GroupMember, PaymentHistory};
+use crate::base::errors::Error;
+use crate::base::types::{
+    AdminAction, AutoShareDetails, BatchItem, BatchSummary, Council, CouncilProposal,
+    DistributionHistory, DistributionHistoryPage, DistributionMode, FeeMode, GroupMember,
+    GroupPage, MemberAmount, PaymentHistory, PaymentHistoryPage, PendingAdmin, PricingTier,
+    QueuedAction, Role, TokenAmount, Tranche,
+};
 
 /// AutoShareTrait defines the interface for the AutoShare contract.
 /// This trait serves as a formal specification that the AutoShareContract implementation
@@ -11,35 +17,128 @@ pub trait AutoShareTrait {
     // Admin Management
     // ============================================================================
 
-    /// Initializes the contract admin. Can only be called once.
-    fn initialize_admin(env: Env, admin: Address);
+    /// Sets the admin at deploy time and supports each listed token at its fee per usage.
+    fn __constructor(env: Env, admin: Address, usage_fees: Vec<TokenAmount>);
 
-    /// Pauses the contract. Only admin can call.
-    fn pause(env: Env, admin: Address);
+    /// Initializes the contract admin. Fails with `AlreadyInitialized` once an
+    /// admin is set.
+    fn initialize_admin(env: Env, admin: Address) -> Result<(), Error>;
 
-    /// Unpauses the contract. Only admin can call.
-    fn unpause(env: Env, admin: Address);
+    /// Pauses the contract. Only admin or a Pauser can call.
+    fn pause(env: Env, caller: Address) -> Result<(), Error>;
+
+    /// Unpauses the contract. Only admin or a Pauser can call.
+    fn unpause(env: Env, caller: Address) -> Result<(), Error>;
 
     /// Returns the current pause status.
     fn get_paused_status(env: Env) -> bool;
 
     /// Returns the current admin address.
-    fn get_admin(env: Env) -> Address;
+    fn get_admin(env: Env) -> Result<Address, Error>;
+
+    /// Proposes a new admin. Only current admin can call.
+    fn propose_admin(
+        env: Env,
+        current_admin: Address,
+        new_admin: Address,
+        expires_at: Option<u64>,
+    ) -> Result<(), Error>;
+
+    /// Accepts a pending admin transfer. Only the proposed admin can call.
+    fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error>;
+
+    /// Cancels a pending admin transfer. Only current admin can call.
+    fn cancel_admin_transfer(env: Env, admin: Address) -> Result<(), Error>;
+
+    /// Returns the pending admin transfer, if any.
+    fn get_pending_admin(env: Env) -> Option<PendingAdmin>;
+
+    // ============================================================================
+    // Roles
+    // ============================================================================
+
+    /// Grants a role to an account. Only admin can call.
+    fn grant_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error>;
+
+    /// Revokes a role from an account. Only admin can call.
+    fn revoke_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error>;
+
+    /// Returns whether an account holds a role.
+    fn has_role(env: Env, role: Role, account: Address) -> bool;
+
+    /// Returns all accounts holding a role.
+    fn get_role_members(env: Env, role: Role) -> Vec<Address>;
+
+    // ============================================================================
+    // Council
+    // ============================================================================
+
+    /// Enables council mode. Only admin can call, and only while no council is set.
+    fn set_council(
+        env: Env,
+        admin: Address,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error>;
+
+    /// Returns the council, if council mode is enabled.
+    fn get_council(env: Env) -> Option<Council>;
+
+    /// Proposes a privileged action. Only council signers can call.
+    fn propose_action(env: Env, signer: Address, action: AdminAction) -> Result<u32, Error>;
+
+    /// Approves a proposal. The action executes once the threshold is reached.
+    fn approve_action(env: Env, signer: Address, proposal_id: u32) -> Result<bool, Error>;
+
+    /// Returns a council proposal.
+    fn get_proposal(env: Env, proposal_id: u32) -> Result<CouncilProposal, Error>;
+
+    // ============================================================================
+    // Timelock
+    // ============================================================================
+
+    /// Sets the delay, in seconds, before timelocked actions can run. Only admin
+    /// can call, and only while no delay is set.
+    fn set_timelock_delay(env: Env, admin: Address, delay: u64) -> Result<(), Error>;
+
+    /// Returns the timelock delay in seconds (0 when disabled).
+    fn get_timelock_delay(env: Env) -> u64;
 
-    /// Transfers admin rights to a new address. Only current admin can call.
-    fn transfer_admin(env: Env, current_admin: Address, new_admin: Address);
+    /// Queues a timelocked action. Returns the queued action id.
+    fn queue_action(env: Env, caller: Address, action: AdminAction) -> Result<u32, Error>;
 
-    /// Withdraws tokens from the contract. Only admin can call.
-    fn withdraw(env: Env, admin: Address, token: Address, amount: i128, recipient: Address);
+    /// Executes a queued action once its ETA has passed. Anyone can call. While a
+    /// council is set, only actions the council approved can run.
+    fn execute_queued_action(env: Env, action_id: u32) -> Result<(), Error>;
+
+    /// Cancels a queued action. Only admin can call.
+    fn cancel_queued_action(env: Env, admin: Address, action_id: u32) -> Result<(), Error>;
+
+    /// Returns a queued action.
+    fn get_queued_action(env: Env, action_id: u32) -> Result<QueuedAction, Error>;
+
+    /// Withdraws collected fee revenue. Only admin or a Treasurer can call.
+    fn withdraw(
+        env: Env,
+        caller: Address,
+        token: Address,
+        amount: i128,
+        recipient: Address,
+    ) -> Result<(), Error>;
 
     /// Returns the contract's balance for a specified token.
     fn get_contract_balance(env: Env, token: Address) -> i128;
 
+    /// Returns the fees collected in a token that can be withdrawn. Prepaid usages
+    /// count once they are spent.
+    fn get_fee_revenue(env: Env, token: Address) -> i128;
+
     // ============================================================================
     // AutoShare Group Management
     // ============================================================================
 
     /// Creates a new AutoShare plan with payment.
+    /// `plan_duration` optionally buys a time subscription plan as well.
     fn create(
         env: Env,
         id: BytesN<32>,
@@ -47,14 +146,22 @@ pub trait AutoShareTrait {
         creator: Address,
         usage_count: u32,
         payment_token: Address,
-    );
+        plan_duration: Option<u64>,
+    ) -> Result<(), Error>;
 
     /// Update members of an existing AutoShare plan.
-    /// Only creator can update. Validates percentages.
-    fn update_members(env: Env, id: BytesN<32>, caller: Address, new_members: Vec<GroupMember>);
+    /// Only creator can update. Shares are basis points summing to 10_000.
+    /// A member may reference a child group, up to 3 levels deep and never
+    /// leading back to this group.
+    fn update_members(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        new_members: Vec<GroupMember>,
+    ) -> Result<(), Error>;
 
     /// Retrieves an existing AutoShare plan.
-    fn get(env: Env, id: BytesN<32>) -> AutoShareDetails;
+    fn get(env: Env, id: BytesN<32>) -> Result<AutoShareDetails, Error>;
 
     /// Retrieves all AutoShare groups.
     fn get_all_groups(env: Env) -> Vec<AutoShareDetails>;
@@ -62,13 +169,27 @@ pub trait AutoShareTrait {
     /// Retrieves all AutoShare groups created by a specific address.
     fn get_groups_by_creator(env: Env, creator: Address) -> Vec<AutoShareDetails>;
 
+    /// Retrieves all AutoShare groups that pay a specific address.
+    fn get_groups_by_member(env: Env, address: Address) -> Vec<AutoShareDetails>;
+
+    /// Returns up to `limit` groups starting at position `start`.
+    fn get_groups_page(env: Env, start: u32, limit: u32) -> Result<GroupPage, Error>;
+
+    /// Returns up to `limit` groups created by `creator` starting at position `start`.
+    fn get_groups_by_creator_page(
+        env: Env,
+        creator: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<GroupPage, Error>;
+
     /// Checks if an address is a member of a specific group.
-    fn is_group_member(env: Env, id: BytesN<32>, address: Address) -> bool;
+    fn is_group_member(env: Env, id: BytesN<32>, address: Address) -> Result<bool, Error>;
 
     /// Returns all members of a group.
-    fn get_group_members(env: Env, id: BytesN<32>) -> Vec<GroupMember>;
+    fn get_group_members(env: Env, id: BytesN<32>) -> Result<Vec<GroupMember>, Error>;
 
-    /// Adds a member to a group with specified percentage.
+    /// Adds a member to a group with the specified share in basis points.
     /// Only the group creator (caller) may add members.
     fn add_group_member(
         env: Env,
@@ -76,34 +197,68 @@ pub trait AutoShareTrait {
         caller: Address,
         address: Address,
         percentage: u32,
-    );
+    ) -> Result<(), Error>;
 
     /// Removes a single member from a group. Only the creator can call; group must be active.
-    /// After removal, remaining percentages may not sum to 100; call update_members to set a valid split.
-    fn remove_group_member(env: Env, id: BytesN<32>, caller: Address, member_address: Address);
+    /// After removal, remaining shares may not sum to 10_000 basis points; call update_members to set a valid split.
+    fn remove_group_member(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        member_address: Address,
+    ) -> Result<(), Error>;
+
+    /// Rescales member splits of groups created before basis-point precision (admin only).
+    /// Returns the number of groups migrated; already migrated groups are skipped.
+    fn migrate_member_splits(env: Env, admin: Address, ids: Vec<BytesN<32>>) -> Result<u32, Error>;
+
+    /// Backfills the lookup indexes for a batch of existing groups. Only admin can call.
+    fn reindex_groups(
+        env: Env,
+        admin: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<Option<u32>, Error>;
+
+    /// Credits the part of the contract's balance of a token that is neither fee
+    /// revenue nor owed to payers or members to the fee revenue, so fees collected
+    /// by earlier versions can be withdrawn. Only admin can call.
+    /// Returns the amount credited.
+    fn migrate_fee_revenue(env: Env, admin: Address, token: Address) -> Result<i128, Error>;
+
+    /// Moves history stored as vectors by earlier versions into per-entry records
+    /// for the given groups and accounts. Only admin can call.
+    fn migrate_history(
+        env: Env,
+        admin: Address,
+        group_ids: Vec<BytesN<32>>,
+        accounts: Vec<Address>,
+    ) -> Result<u32, Error>;
 
     /// Deactivates a group. Only the creator can deactivate.
-    fn deactivate_group(env: Env, id: BytesN<32>, caller: Address);
+    fn deactivate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error>;
 
     /// Activates a group. Only the creator can activate.
-    fn activate_group(env: Env, id: BytesN<32>, caller: Address);
+    fn activate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error>;
 
     /// Returns whether a group is active.
-    fn is_group_active(env: Env, id: BytesN<32>) -> bool;
+    fn is_group_active(env: Env, id: BytesN<32>) -> Result<bool, Error>;
 
     /// Permanently deletes a group. Only creator or admin can delete.
-    /// Group must be deactivated first and have 0 remaining usages.
-    fn delete_group(env: Env, id: BytesN<32>, caller: Address);
+    /// Group must be deactivated first. Remaining usages are refunded to whoever paid
+    /// for them, at the price and in the token they were paid with.
+    /// Fails while another group passes shares down to it.
+    fn delete_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error>;
 
     // ============================================================================
     // Token Management
     // ============================================================================
 
-    /// Adds a supported payment token (admin only).
-    fn add_supported_token(env: Env, token: Address, admin: Address);
+    /// Adds a supported payment token (admin or TokenManager).
+    fn add_supported_token(env: Env, token: Address, caller: Address) -> Result<(), Error>;
 
-    /// Removes a supported payment token (admin only).
-    fn remove_supported_token(env: Env, token: Address, admin: Address);
+    /// Removes a supported payment token (admin or TokenManager).
+    fn remove_supported_token(env: Env, token: Address, caller: Address) -> Result<(), Error>;
 
     /// Returns all supported payment tokens.
     fn get_supported_tokens(env: Env) -> Vec<Address>;
@@ -112,30 +267,175 @@ pub trait AutoShareTrait {
     fn is_token_supported(env: Env, token: Address) -> bool;
 
     /// Distributes a payment among group members based on their percentages.
-    fn distribute(env: Env, id: BytesN<32>, token: Address, amount: i128, sender: Address);
+    /// Shares of child-group members are split again by the child groups.
+    fn distribute(
+        env: Env,
+        id: BytesN<32>,
+        token: Address,
+        amount: i128,
+        sender: Address,
+    ) -> Result<(), Error>;
+
+    /// Distributes several tokens to a group in one call. Each token is split with the
+    /// group's members, a single usage is spent and one history entry holds every token.
+    /// Fixed-amount members are paid from the token their amount is set in.
+    fn distribute_multi(
+        env: Env,
+        id: BytesN<32>,
+        amounts: Vec<TokenAmount>,
+        sender: Address,
+    ) -> Result<(), Error>;
+
+    /// Makes up to 50 distributions in one call with a single transfer per token from
+    /// the sender. All distributions succeed or the whole batch fails.
+    fn distribute_batch(
+        env: Env,
+        sender: Address,
+        items: Vec<BatchItem>,
+    ) -> Result<BatchSummary, Error>;
+
+    /// Returns what each member would receive from distributing `amount` of `token` now.
+    /// Read-only; fails with the same errors `distribute` would.
+    fn preview_distribution(
+        env: Env,
+        id: BytesN<32>,
+        token: Address,
+        amount: i128,
+    ) -> Result<Vec<MemberAmount>, Error>;
+
+    // ============================================================================
+    // Tranches
+    // ============================================================================
+
+    /// Replaces a group's waterfall of capped tranches, each with its own member split.
+    /// An empty list returns the group to its regular members. Only the creator can change it.
+    fn set_tranches(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        tranches: Vec<Tranche>,
+    ) -> Result<(), Error>;
+
+    /// Returns a group's tranches, empty when it has none.
+    fn get_tranches(env: Env, id: BytesN<32>) -> Result<Vec<Tranche>, Error>;
+
+    /// Returns the total amount of a token the group has split among its members
+    /// while it had tranches.
+    fn get_cumulative_received(env: Env, id: BytesN<32>, token: Address) -> i128;
+
+    // ============================================================================
+    // Claimable Balances
+    // ============================================================================
+
+    /// Sets whether a group's distributions are pushed to members or credited for claiming.
+    /// Only the creator can change it. Groups default to push mode.
+    fn set_distribution_mode(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        mode: DistributionMode,
+    ) -> Result<(), Error>;
+
+    /// Returns the distribution mode of a group.
+    fn get_distribution_mode(env: Env, id: BytesN<32>) -> Result<DistributionMode, Error>;
+
+    /// Withdraws a member's claimable balance of one token. Returns the amount claimed.
+    fn claim(env: Env, member: Address, token: Address) -> Result<i128, Error>;
+
+    /// Withdraws all of a member's claimable balances. Returns the amount claimed per token.
+    fn claim_all(env: Env, member: Address) -> Result<Vec<TokenAmount>, Error>;
+
+    /// Returns a member's claimable balance of a token.
+    fn get_claimable(env: Env, member: Address, token: Address) -> i128;
+
+    // ============================================================================
+    // Group Splitter Contracts
+    // ============================================================================
+
+    /// Sets the WASM hash used to deploy per-group splitter contracts (admin only).
+    /// Once set, `create` deploys a splitter for every new group.
+    fn set_splitter_wasm_hash(env: Env, admin: Address, wasm_hash: BytesN<32>)
+        -> Result<(), Error>;
+
+    /// Returns the WASM hash used to deploy splitter contracts.
+    fn get_splitter_wasm_hash(env: Env) -> Result<BytesN<32>, Error>;
+
+    /// Deploys a splitter contract for an existing group. Only the creator can call.
+    fn deploy_group_splitter(env: Env, id: BytesN<32>, caller: Address) -> Result<Address, Error>;
+
+    /// Returns the address of a group's splitter contract.
+    fn get_group_splitter(env: Env, id: BytesN<32>) -> Result<Address, Error>;
+
+    /// Returns the balance of a token waiting in a group's splitter to be settled.
+    fn get_unsettled_balance(env: Env, id: BytesN<32>, token: Address) -> Result<i128, Error>;
+
+    /// Splits the token balance received by a group's splitter among its members.
+    /// Anyone can call. Consumes one usage and records a deposit distribution.
+    /// Returns the amount settled.
+    fn settle(env: Env, group_id: BytesN<32>, token: Address) -> Result<i128, Error>;
 
     // ============================================================================
     // Payment Configuration
     // ============================================================================
 
-    /// Sets the usage fee (admin only).
-    fn set_usage_fee(env: Env, fee: u32, admin: Address);
+    /// Sets the fee per usage for a supported token, in its smallest units (admin or FeeManager).
+    fn set_usage_fee(env: Env, token: Address, fee: i128, caller: Address) -> Result<(), Error>;
+
+    /// Returns the fee per usage charged in a token. Fails with `UsageFeeNotSet`
+    /// when the token has no fee.
+    fn get_usage_fee(env: Env, token: Address) -> Result<i128, Error>;
+
+    /// Replaces the volume discount tiers for usage purchases (admin or FeeManager).
+    fn set_pricing_tiers(env: Env, caller: Address, tiers: Vec<PricingTier>) -> Result<(), Error>;
 
-    /// Returns the current usage fee.
-    fn get_usage_fee(env: Env) -> u32;
+    /// Returns the volume discount tiers for usage purchases.
+    fn get_pricing_tiers(env: Env) -> Vec<PricingTier>;
+
+    /// Returns what buying `count` usages in a token costs now, after any volume discount.
+    fn quote_usages(env: Env, token: Address, count: u32) -> Result<i128, Error>;
+
+    /// Sets the protocol fee, in basis points, taken from each distribution of
+    /// groups in percentage mode (admin or FeeManager).
+    fn set_protocol_fee(env: Env, caller: Address, fee_bps: u32) -> Result<(), Error>;
+
+    /// Returns the protocol fee in basis points.
+    fn get_protocol_fee(env: Env) -> u32;
+
+    /// Sets whether a group pays with prepaid usages or a percentage of each distribution.
+    /// Only the creator can change it. Percentage mode needs a protocol fee to be set.
+    /// Groups default to prepaid mode.
+    fn set_fee_mode(env: Env, id: BytesN<32>, caller: Address, mode: FeeMode) -> Result<(), Error>;
+
+    /// Returns the fee mode of a group.
+    fn get_fee_mode(env: Env, id: BytesN<32>) -> Result<FeeMode, Error>;
 
     // ============================================================================
     // Subscription Management
     // ============================================================================
 
-    /// Tops up a group's subscription with additional usages.
+    /// Tops up a group's subscription with additional usages and, optionally,
+    /// the time plan lasting `plan_duration` seconds.
     fn topup_subscription(
         env: Env,
         id: BytesN<32>,
         additional_usages: u32,
         payment_token: Address,
         payer: Address,
-    );
+        plan_duration: Option<u64>,
+    ) -> Result<(), Error>;
+
+    /// Sets the price of a time plan in a token, or removes it when `price` is
+    /// `None` (admin or FeeManager).
+    fn set_subscription_plan(
+        env: Env,
+        caller: Address,
+        token: Address,
+        duration: u64,
+        price: Option<i128>,
+    ) -> Result<(), Error>;
+
+    /// Returns the price in a token of the time plan lasting `duration` seconds.
+    fn get_subscription_plan(env: Env, token: Address, duration: u64) -> Option<i128>;
 
     // ============================================================================
     // Payment History
@@ -147,6 +447,22 @@ pub trait AutoShareTrait {
     /// Returns all payment history for a group.
     fn get_group_payment_history(env: Env, id: BytesN<32>) -> Vec<PaymentHistory>;
 
+    /// Returns up to `limit` payments made by a user starting at position `start`.
+    fn get_user_payment_history_page(
+        env: Env,
+        user: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<PaymentHistoryPage, Error>;
+
+    /// Returns up to `limit` payments for a group starting at position `start`.
+    fn get_group_payment_history_page(
+        env: Env,
+        id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<PaymentHistoryPage, Error>;
+
     // ============================================================================
     // Distribution History
     // ============================================================================
@@ -157,13 +473,32 @@ pub trait AutoShareTrait {
     /// Returns all distribution history for a member.
     fn get_member_distributions(env: Env, member: Address) -> Vec<DistributionHistory>;
 
+    /// Returns up to `limit` distributions of a group starting at position `start`.
+    fn get_group_distributions_page(
+        env: Env,
+        id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<DistributionHistoryPage, Error>;
+
+    /// Returns up to `limit` distributions received by a member starting at position `start`.
+    fn get_member_distributions_page(
+        env: Env,
+        member: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<DistributionHistoryPage, Error>;
+
     // ============================================================================
     // Usage Tracking
     // ============================================================================
 
     /// Returns the remaining usages for a group.
-    fn get_remaining_usages(env: Env, id: BytesN<32>) -> u32;
+    fn get_remaining_usages(env: Env, id: BytesN<32>) -> Result<u32, Error>;
+
+    /// Returns when a group's time subscription ends, or 0 if it never had one.
+    fn get_subscription_expiry(env: Env, id: BytesN<32>) -> Result<u64, Error>;
 
     /// Returns the total usages paid for a group.
-    fn get_total_usages_paid(env: Env, id: BytesN<32>) -> u32;
+    fn get_total_usages_paid(env: Env, id: BytesN<32>) -> Result<u32, Error>;
 }
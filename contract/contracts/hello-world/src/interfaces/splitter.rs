@@ -0,0 +1,18 @@
+use soroban_sdk::{contractclient, Address, BytesN, Env, Vec};
+
+use crate::base::types::MemberAmount;
+
+/// Interface of the per-group splitter contracts deployed by AutoShare.
+/// Each splitter holds the tokens paid to its address until AutoShare
+/// instructs it to pay them out to the group's members.
+#[contractclient(name = "GroupSplitterClient")]
+pub trait GroupSplitterInterface {
+    /// Returns the factory contract that controls payouts.
+    fn factory(env: Env) -> Address;
+
+    /// Returns the id of the group the splitter belongs to.
+    fn group_id(env: Env) -> BytesN<32>;
+
+    /// Transfers the given amounts of `token` out of the splitter. Only the factory can call.
+    fn payout(env: Env, token: Address, payouts: Vec<MemberAmount>);
+}
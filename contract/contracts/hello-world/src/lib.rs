@@ -1,6 +1,8 @@
 #![no_std]
 use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Vec};
 
+use base::errors::Error;
+
 // 1. Declare the foundational modules (Requirement: Modular Structure)
 pub mod base {
     pub mod errors;
@@ -10,6 +12,7 @@ pub mod base {
 
 pub mod interfaces {
     pub mod autoshare;
+    pub mod splitter;
 }
 
 // 2. Declare the main logic file where the functions are implemented
@@ -27,19 +30,25 @@ impl AutoShareContract {
     // Admin Management
     // ============================================================================
 
-    /// Initializes the contract admin. Can only be called once.
-    pub fn initialize_admin(env: Env, admin: Address) {
-        autoshare_logic::initialize_admin(env, admin);
+    /// Sets the admin at deploy time and supports each listed token at its fee per usage.
+    pub fn __constructor(env: Env, admin: Address, usage_fees: Vec<base::types::TokenAmount>) {
+        autoshare_logic::initialize(env, admin, usage_fees);
+    }
+
+    /// Initializes the contract admin. Fails with `AlreadyInitialized` once an
+    /// admin is set, which the constructor always does.
+    pub fn initialize_admin(env: Env, admin: Address) -> Result<(), Error> {
+        autoshare_logic::initialize_admin(env, admin)
     }
 
-    /// Pauses the contract. Only admin can call.
-    pub fn pause(env: Env, admin: Address) {
-        autoshare_logic::pause(env, admin).unwrap();
+    /// Pauses the contract. Only admin or a Pauser can call.
+    pub fn pause(env: Env, caller: Address) -> Result<(), Error> {
+        autoshare_logic::pause(env, caller)
     }
 
-    /// Unpauses the contract. Only admin can call.
-    pub fn unpause(env: Env, admin: Address) {
-        autoshare_logic::unpause(env, admin).unwrap();
+    /// Unpauses the contract. Only admin or a Pauser can call.
+    pub fn unpause(env: Env, caller: Address) -> Result<(), Error> {
+        autoshare_logic::unpause(env, caller)
     }
 
     /// Returns the current pause status.
@@ -53,6 +62,7 @@ impl AutoShareContract {
 
     /// Creates a new AutoShare plan with payment.
     /// Requirement: create_autoshare should store data, accept payment, and emit an event.
+    /// `plan_duration` optionally buys a time subscription plan as well.
     pub fn create(
         env: Env,
         id: BytesN<32>,
@@ -60,26 +70,36 @@ impl AutoShareContract {
         creator: Address,
         usage_count: u32,
         payment_token: Address,
-    ) {
-        autoshare_logic::create_autoshare(env, id, name, creator, usage_count, payment_token)
-            .unwrap();
+        plan_duration: Option<u64>,
+    ) -> Result<(), Error> {
+        autoshare_logic::create_autoshare(
+            env,
+            id,
+            name,
+            creator,
+            usage_count,
+            payment_token,
+            plan_duration,
+        )
     }
 
     /// Update members of an existing AutoShare plan.
-    /// Requirement: Only creator can update. Validates percentages.
+    /// Requirement: Only creator can update. Shares are basis points summing to 10_000.
+    /// A member may reference a child group, up to 3 levels deep and never
+    /// leading back to this group.
     pub fn update_members(
         env: Env,
         id: BytesN<32>,
         caller: Address,
         new_members: Vec<base::types::GroupMember>,
-    ) {
-        autoshare_logic::update_members(env, id, caller, new_members).unwrap();
+    ) -> Result<(), Error> {
+        autoshare_logic::update_members(env, id, caller, new_members)
     }
 
     /// Retrieves an existing AutoShare plan.
     /// Requirement: get_autoshare should return the plan details.
-    pub fn get(env: Env, id: BytesN<32>) -> base::types::AutoShareDetails {
-        autoshare_logic::get_autoshare(env, id).unwrap()
+    pub fn get(env: Env, id: BytesN<32>) -> Result<base::types::AutoShareDetails, Error> {
+        autoshare_logic::get_autoshare(env, id)
     }
 
     /// Retrieves all AutoShare groups.
@@ -92,16 +112,44 @@ impl AutoShareContract {
         autoshare_logic::get_groups_by_creator(env, creator)
     }
 
+    /// Retrieves all AutoShare groups that pay a specific address.
+    pub fn get_groups_by_member(env: Env, address: Address) -> Vec<base::types::AutoShareDetails> {
+        autoshare_logic::get_groups_by_member(env, address)
+    }
+
+    /// Returns up to `limit` groups starting at position `start`.
+    /// `limit` must be between 1 and `MAX_PAGE_SIZE`.
+    pub fn get_groups_page(
+        env: Env,
+        start: u32,
+        limit: u32,
+    ) -> Result<base::types::GroupPage, Error> {
+        autoshare_logic::get_groups_page(env, start, limit)
+    }
+
+    /// Returns up to `limit` groups created by `creator` starting at position `start`.
+    pub fn get_groups_by_creator_page(
+        env: Env,
+        creator: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<base::types::GroupPage, Error> {
+        autoshare_logic::get_groups_by_creator_page(env, creator, start, limit)
+    }
+
     /// Checks if an address is a member of a specific group.
-    pub fn is_group_member(env: Env, id: BytesN<32>, address: Address) -> bool {
-        autoshare_logic::is_group_member(env, id, address).unwrap()
+    pub fn is_group_member(env: Env, id: BytesN<32>, address: Address) -> Result<bool, Error> {
+        autoshare_logic::is_group_member(env, id, address)
     }
 
-    pub fn get_group_members(env: Env, id: BytesN<32>) -> Vec<base::types::GroupMember> {
-        autoshare_logic::get_group_members(env, id).unwrap()
+    pub fn get_group_members(
+        env: Env,
+        id: BytesN<32>,
+    ) -> Result<Vec<base::types::GroupMember>, Error> {
+        autoshare_logic::get_group_members(env, id)
     }
 
-    /// Adds a member to a group with specified percentage.
+    /// Adds a member to a group with the specified share in basis points.
     /// Only the group creator (caller) may add members.
     pub fn add_group_member(
         env: Env,
@@ -109,50 +157,245 @@ impl AutoShareContract {
         caller: Address,
         address: Address,
         percentage: u32,
-    ) {
-        autoshare_logic::add_group_member(env, id, caller, address, percentage).unwrap();
+    ) -> Result<(), Error> {
+        autoshare_logic::add_group_member(env, id, caller, address, percentage)
     }
 
     /// Removes a single member from a group. Only the creator can call; group must be active.
-    /// After removal, remaining percentages may not sum to 100; call update_members to set a valid split.
-    pub fn remove_group_member(env: Env, id: BytesN<32>, caller: Address, member_address: Address) {
-        autoshare_logic::remove_group_member(env, id, caller, member_address).unwrap();
+    /// After removal, remaining shares may not sum to 10_000 basis points; call update_members to set a valid split.
+    pub fn remove_group_member(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        member_address: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::remove_group_member(env, id, caller, member_address)
+    }
+
+    /// Rescales member splits of groups created before basis-point precision (admin only).
+    /// Returns the number of groups migrated; already migrated groups are skipped.
+    pub fn migrate_member_splits(
+        env: Env,
+        admin: Address,
+        ids: Vec<BytesN<32>>,
+    ) -> Result<u32, Error> {
+        autoshare_logic::migrate_member_splits(env, admin, ids)
+    }
+
+    /// Backfills the lookup indexes for a batch of existing groups. Only admin can call.
+    /// Returns the `start` of the next batch, or `None` once every group is indexed.
+    pub fn reindex_groups(
+        env: Env,
+        admin: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<Option<u32>, Error> {
+        autoshare_logic::reindex_groups(env, admin, start, limit)
+    }
+
+    /// Credits the part of the contract's balance of a token that is neither fee
+    /// revenue nor owed to payers or members to the fee revenue, so fees collected
+    /// by earlier versions can be withdrawn. Only admin can call.
+    /// Returns the amount credited.
+    pub fn migrate_fee_revenue(env: Env, admin: Address, token: Address) -> Result<i128, Error> {
+        autoshare_logic::migrate_fee_revenue(env, admin, token)
+    }
+
+    /// Moves history stored as vectors by earlier versions into per-entry records
+    /// for the given groups and accounts. Only admin can call.
+    /// Returns the number of entries moved.
+    pub fn migrate_history(
+        env: Env,
+        admin: Address,
+        group_ids: Vec<BytesN<32>>,
+        accounts: Vec<Address>,
+    ) -> Result<u32, Error> {
+        autoshare_logic::migrate_history(env, admin, group_ids, accounts)
     }
 
     /// Deactivates a group. Only the creator can deactivate.
-    pub fn deactivate_group(env: Env, id: BytesN<32>, caller: Address) {
-        autoshare_logic::deactivate_group(env, id, caller).unwrap();
+    pub fn deactivate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+        autoshare_logic::deactivate_group(env, id, caller)
     }
 
     /// Activates a group. Only the creator can activate.
-    pub fn activate_group(env: Env, id: BytesN<32>, caller: Address) {
-        autoshare_logic::activate_group(env, id, caller).unwrap();
+    pub fn activate_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+        autoshare_logic::activate_group(env, id, caller)
     }
 
     /// Returns whether a group is active.
-    pub fn is_group_active(env: Env, id: BytesN<32>) -> bool {
-        autoshare_logic::is_group_active(env, id).unwrap()
+    pub fn is_group_active(env: Env, id: BytesN<32>) -> Result<bool, Error> {
+        autoshare_logic::is_group_active(env, id)
     }
 
     /// Permanently deletes a group. Only creator or admin can delete.
-    /// Group must be deactivated first and have 0 remaining usages.
-    pub fn delete_group(env: Env, id: BytesN<32>, caller: Address) {
-        autoshare_logic::delete_group(env, id, caller).unwrap();
+    /// Group must be deactivated first. Remaining usages are refunded to whoever paid
+    /// for them, at the price and in the token they were paid with.
+    /// Fails while another group passes shares down to it.
+    pub fn delete_group(env: Env, id: BytesN<32>, caller: Address) -> Result<(), Error> {
+        autoshare_logic::delete_group(env, id, caller)
     }
 
     /// Returns the current admin address.
-    pub fn get_admin(env: Env) -> Address {
-        autoshare_logic::get_admin(env).unwrap()
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        autoshare_logic::get_admin(env)
+    }
+
+    /// Proposes a new admin. Only current admin can call.
+    /// Admin rights move once the new admin calls `accept_admin`, before
+    /// `expires_at` (ledger timestamp) when one is given.
+    pub fn propose_admin(
+        env: Env,
+        current_admin: Address,
+        new_admin: Address,
+        expires_at: Option<u64>,
+    ) -> Result<(), Error> {
+        autoshare_logic::propose_admin(env, current_admin, new_admin, expires_at)
+    }
+
+    /// Accepts a pending admin transfer. Only the proposed admin can call.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        autoshare_logic::accept_admin(env, new_admin)
+    }
+
+    /// Cancels a pending admin transfer. Only current admin can call.
+    pub fn cancel_admin_transfer(env: Env, admin: Address) -> Result<(), Error> {
+        autoshare_logic::cancel_admin_transfer(env, admin)
+    }
+
+    /// Returns the pending admin transfer, if any.
+    pub fn get_pending_admin(env: Env) -> Option<base::types::PendingAdmin> {
+        autoshare_logic::get_pending_admin(env)
+    }
+
+    // ============================================================================
+    // Roles
+    // ============================================================================
+
+    /// Grants a role to an account. Only admin can call.
+    pub fn grant_role(
+        env: Env,
+        admin: Address,
+        role: base::types::Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::grant_role(env, admin, role, account)
+    }
+
+    /// Revokes a role from an account. Only admin can call.
+    pub fn revoke_role(
+        env: Env,
+        admin: Address,
+        role: base::types::Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::revoke_role(env, admin, role, account)
+    }
+
+    /// Returns whether an account holds a role.
+    pub fn has_role(env: Env, role: base::types::Role, account: Address) -> bool {
+        autoshare_logic::has_role(env, role, account)
+    }
+
+    /// Returns all accounts holding a role.
+    pub fn get_role_members(env: Env, role: base::types::Role) -> Vec<Address> {
+        autoshare_logic::get_role_members(env, role)
+    }
+
+    // ============================================================================
+    // Council
+    // ============================================================================
+
+    /// Enables council mode. Only admin can call, and only while no council is set.
+    /// Afterwards admin and role entrypoints fail with `CouncilModeActive` and privileged
+    /// changes are made through `propose_action` / `approve_action`.
+    pub fn set_council(
+        env: Env,
+        admin: Address,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_council(env, admin, signers, threshold)
+    }
+
+    /// Returns the council, if council mode is enabled.
+    pub fn get_council(env: Env) -> Option<base::types::Council> {
+        autoshare_logic::get_council(env)
+    }
+
+    /// Proposes a privileged action. Only council signers can call. The proposer's
+    /// approval is counted. Returns the proposal id.
+    pub fn propose_action(
+        env: Env,
+        signer: Address,
+        action: base::types::AdminAction,
+    ) -> Result<u32, Error> {
+        autoshare_logic::propose_action(env, signer, action)
     }
 
-    /// Transfers admin rights to a new address. Only current admin can call.
-    pub fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) {
-        autoshare_logic::transfer_admin(env, current_admin, new_admin).unwrap();
+    /// Approves a proposal. Only council signers can call. The action executes
+    /// once the threshold is reached; returns whether it has executed.
+    pub fn approve_action(env: Env, signer: Address, proposal_id: u32) -> Result<bool, Error> {
+        autoshare_logic::approve_action(env, signer, proposal_id)
+    }
+
+    /// Returns a council proposal.
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Result<base::types::CouncilProposal, Error> {
+        autoshare_logic::get_proposal(env, proposal_id)
+    }
+
+    // ============================================================================
+    // Timelock
+    // ============================================================================
+
+    /// Sets the delay, in seconds, before timelocked actions (withdraw, usage fee,
+    /// pricing tier, subscription plan and protocol fee changes, token removal, delay
+    /// changes) can run. Only admin can call, and only while no delay is set; afterwards
+    /// the delay is changed through `queue_action`.
+    pub fn set_timelock_delay(env: Env, admin: Address, delay: u64) -> Result<(), Error> {
+        autoshare_logic::set_timelock_delay(env, admin, delay)
     }
 
-    /// Withdraws tokens from the contract. Only admin can call.
-    pub fn withdraw(env: Env, admin: Address, token: Address, amount: i128, recipient: Address) {
-        autoshare_logic::withdraw(env, admin, token, amount, recipient).unwrap();
+    /// Returns the timelock delay in seconds (0 when disabled).
+    pub fn get_timelock_delay(env: Env) -> u64 {
+        autoshare_logic::get_timelock_delay(env)
+    }
+
+    /// Queues a timelocked action. The caller needs the role the direct entrypoint
+    /// requires. Returns the queued action id.
+    pub fn queue_action(
+        env: Env,
+        caller: Address,
+        action: base::types::AdminAction,
+    ) -> Result<u32, Error> {
+        autoshare_logic::queue_action(env, caller, action)
+    }
+
+    /// Executes a queued action once its ETA has passed. Anyone can call. While a
+    /// council is set, only actions the council approved can run.
+    pub fn execute_queued_action(env: Env, action_id: u32) -> Result<(), Error> {
+        autoshare_logic::execute_queued_action(env, action_id)
+    }
+
+    /// Cancels a queued action. Only admin can call.
+    pub fn cancel_queued_action(env: Env, admin: Address, action_id: u32) -> Result<(), Error> {
+        autoshare_logic::cancel_queued_action(env, admin, action_id)
+    }
+
+    /// Returns a queued action.
+    pub fn get_queued_action(env: Env, action_id: u32) -> Result<base::types::QueuedAction, Error> {
+        autoshare_logic::get_queued_action(env, action_id)
+    }
+
+    /// Withdraws collected fee revenue. Only admin or a Treasurer can call.
+    pub fn withdraw(
+        env: Env,
+        caller: Address,
+        token: Address,
+        amount: i128,
+        recipient: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::withdraw(env, caller, token, amount, recipient)
     }
 
     /// Returns the contract's balance for a specified token.
@@ -160,18 +403,24 @@ impl AutoShareContract {
         autoshare_logic::get_contract_balance(env, token)
     }
 
+    /// Returns the fees collected in a token that can be withdrawn. Prepaid usages
+    /// count once they are spent.
+    pub fn get_fee_revenue(env: Env, token: Address) -> i128 {
+        autoshare_logic::get_fee_revenue(env, token)
+    }
+
     // ============================================================================
     // Token Management
     // ============================================================================
 
-    /// Adds a supported payment token (admin only).
-    pub fn add_supported_token(env: Env, token: Address, admin: Address) {
-        autoshare_logic::add_supported_token(env, token, admin).unwrap();
+    /// Adds a supported payment token (admin or TokenManager).
+    pub fn add_supported_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+        autoshare_logic::add_supported_token(env, token, caller)
     }
 
-    /// Removes a supported payment token (admin only).
-    pub fn remove_supported_token(env: Env, token: Address, admin: Address) {
-        autoshare_logic::remove_supported_token(env, token, admin).unwrap();
+    /// Removes a supported payment token (admin or TokenManager).
+    pub fn remove_supported_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+        autoshare_logic::remove_supported_token(env, token, caller)
     }
 
     /// Returns all supported payment tokens.
@@ -185,38 +434,265 @@ impl AutoShareContract {
     }
 
     /// Distributes a payment among group members based on their percentages.
-    pub fn distribute(env: Env, id: BytesN<32>, token: Address, amount: i128, sender: Address) {
-        autoshare_logic::distribute(env, id, token, amount, sender).unwrap();
+    /// Shares of child-group members are split again by the child groups.
+    pub fn distribute(
+        env: Env,
+        id: BytesN<32>,
+        token: Address,
+        amount: i128,
+        sender: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::distribute(env, id, token, amount, sender)
+    }
+
+    /// Distributes several tokens to a group in one call. Each token is split with the
+    /// group's members, a single usage is spent and one history entry holds every token.
+    /// Fixed-amount members are paid from the token their amount is set in.
+    pub fn distribute_multi(
+        env: Env,
+        id: BytesN<32>,
+        amounts: Vec<base::types::TokenAmount>,
+        sender: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::distribute_multi(env, id, amounts, sender)
+    }
+
+    /// Makes up to 50 distributions in one call with a single transfer per token from
+    /// the sender. All distributions succeed or the whole batch fails.
+    pub fn distribute_batch(
+        env: Env,
+        sender: Address,
+        items: Vec<base::types::BatchItem>,
+    ) -> Result<base::types::BatchSummary, Error> {
+        autoshare_logic::distribute_batch(env, sender, items)
+    }
+
+    /// Returns what each member would receive from distributing `amount` of `token` now.
+    /// Read-only; fails with the same errors `distribute` would.
+    pub fn preview_distribution(
+        env: Env,
+        id: BytesN<32>,
+        token: Address,
+        amount: i128,
+    ) -> Result<Vec<base::types::MemberAmount>, Error> {
+        autoshare_logic::preview_distribution(env, id, token, amount)
+    }
+
+    // ============================================================================
+    // Tranches
+    // ============================================================================
+
+    /// Replaces a group's waterfall of capped tranches, each with its own member split.
+    /// An empty list returns the group to its regular members. Only the creator can change it.
+    pub fn set_tranches(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        tranches: Vec<base::types::Tranche>,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_tranches(env, id, caller, tranches)
+    }
+
+    /// Returns a group's tranches, empty when it has none.
+    pub fn get_tranches(env: Env, id: BytesN<32>) -> Result<Vec<base::types::Tranche>, Error> {
+        autoshare_logic::get_tranches(env, id)
+    }
+
+    /// Returns the total amount of a token the group has split among its members
+    /// while it had tranches.
+    pub fn get_cumulative_received(env: Env, id: BytesN<32>, token: Address) -> i128 {
+        autoshare_logic::get_cumulative_received(env, id, token)
+    }
+
+    // ============================================================================
+    // Claimable Balances
+    // ============================================================================
+
+    /// Sets whether a group's distributions are pushed to members or credited for claiming.
+    /// Only the creator can change it. Groups default to push mode.
+    pub fn set_distribution_mode(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        mode: base::types::DistributionMode,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_distribution_mode(env, id, caller, mode)
+    }
+
+    /// Returns the distribution mode of a group.
+    pub fn get_distribution_mode(
+        env: Env,
+        id: BytesN<32>,
+    ) -> Result<base::types::DistributionMode, Error> {
+        autoshare_logic::get_distribution_mode(env, id)
+    }
+
+    /// Withdraws a member's claimable balance of one token. Returns the amount claimed.
+    pub fn claim(env: Env, member: Address, token: Address) -> Result<i128, Error> {
+        autoshare_logic::claim(env, member, token)
+    }
+
+    /// Withdraws all of a member's claimable balances. Returns the amount claimed per token.
+    pub fn claim_all(env: Env, member: Address) -> Result<Vec<base::types::TokenAmount>, Error> {
+        autoshare_logic::claim_all(env, member)
+    }
+
+    /// Returns a member's claimable balance of a token.
+    pub fn get_claimable(env: Env, member: Address, token: Address) -> i128 {
+        autoshare_logic::get_claimable(env, member, token)
+    }
+
+    // ============================================================================
+    // Group Splitter Contracts
+    // ============================================================================
+
+    /// Sets the WASM hash used to deploy per-group splitter contracts (admin only).
+    /// Once set, `create` deploys a splitter for every new group.
+    pub fn set_splitter_wasm_hash(
+        env: Env,
+        admin: Address,
+        wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_splitter_wasm_hash(env, admin, wasm_hash)
+    }
+
+    /// Returns the WASM hash used to deploy splitter contracts.
+    pub fn get_splitter_wasm_hash(env: Env) -> Result<BytesN<32>, Error> {
+        autoshare_logic::get_splitter_wasm_hash(env)
+    }
+
+    /// Deploys a splitter contract for an existing group. Only the creator can call.
+    pub fn deploy_group_splitter(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+    ) -> Result<Address, Error> {
+        autoshare_logic::deploy_group_splitter(env, id, caller)
+    }
+
+    /// Returns the address of a group's splitter contract.
+    pub fn get_group_splitter(env: Env, id: BytesN<32>) -> Result<Address, Error> {
+        autoshare_logic::get_group_splitter(env, id)
+    }
+
+    /// Returns the balance of a token waiting in a group's splitter to be settled.
+    pub fn get_unsettled_balance(env: Env, id: BytesN<32>, token: Address) -> Result<i128, Error> {
+        autoshare_logic::get_unsettled_balance(env, id, token)
+    }
+
+    /// Splits the token balance received by a group's splitter among its members.
+    /// Anyone can call. Consumes one usage and records a deposit distribution.
+    /// Returns the amount settled.
+    pub fn settle(env: Env, group_id: BytesN<32>, token: Address) -> Result<i128, Error> {
+        autoshare_logic::settle(env, group_id, token)
     }
 
     // ============================================================================
     // Payment Configuration
     // ============================================================================
 
-    /// Sets the usage fee (admin only).
-    pub fn set_usage_fee(env: Env, fee: u32, admin: Address) {
-        autoshare_logic::set_usage_fee(env, fee, admin).unwrap();
+    /// Sets the fee per usage for a supported token, in its smallest units (admin or FeeManager).
+    pub fn set_usage_fee(
+        env: Env,
+        token: Address,
+        fee: i128,
+        caller: Address,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_usage_fee(env, token, fee, caller)
+    }
+
+    /// Returns the fee per usage charged in a token. Fails with `UsageFeeNotSet`
+    /// when the token has no fee.
+    pub fn get_usage_fee(env: Env, token: Address) -> Result<i128, Error> {
+        autoshare_logic::get_usage_fee(env, token)
+    }
+
+    /// Replaces the volume discount tiers for usage purchases (admin or FeeManager).
+    pub fn set_pricing_tiers(
+        env: Env,
+        caller: Address,
+        tiers: Vec<base::types::PricingTier>,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_pricing_tiers(env, caller, tiers)
+    }
+
+    /// Returns the volume discount tiers for usage purchases.
+    pub fn get_pricing_tiers(env: Env) -> Vec<base::types::PricingTier> {
+        autoshare_logic::get_pricing_tiers(env)
+    }
+
+    /// Returns what buying `count` usages in a token costs now, after any volume discount.
+    pub fn quote_usages(env: Env, token: Address, count: u32) -> Result<i128, Error> {
+        autoshare_logic::quote_usages(env, token, count)
+    }
+
+    /// Sets the protocol fee, in basis points, taken from each distribution of
+    /// groups in percentage mode (admin or FeeManager).
+    pub fn set_protocol_fee(env: Env, caller: Address, fee_bps: u32) -> Result<(), Error> {
+        autoshare_logic::set_protocol_fee(env, caller, fee_bps)
+    }
+
+    /// Returns the protocol fee in basis points.
+    pub fn get_protocol_fee(env: Env) -> u32 {
+        autoshare_logic::get_protocol_fee(env)
     }
 
-    /// Returns the current usage fee.
-    pub fn get_usage_fee(env: Env) -> u32 {
-        autoshare_logic::get_usage_fee(env)
+    /// Sets whether a group pays with prepaid usages or a percentage of each distribution.
+    /// Only the creator can change it. Percentage mode needs a protocol fee to be set.
+    /// Groups default to prepaid mode.
+    pub fn set_fee_mode(
+        env: Env,
+        id: BytesN<32>,
+        caller: Address,
+        mode: base::types::FeeMode,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_fee_mode(env, id, caller, mode)
+    }
+
+    /// Returns the fee mode of a group.
+    pub fn get_fee_mode(env: Env, id: BytesN<32>) -> Result<base::types::FeeMode, Error> {
+        autoshare_logic::get_fee_mode(env, id)
     }
 
     // ============================================================================
     // Subscription Management
     // ============================================================================
 
-    /// Tops up a group's subscription with additional usages.
+    /// Tops up a group's subscription with additional usages and, optionally,
+    /// the time plan lasting `plan_duration` seconds.
     pub fn topup_subscription(
         env: Env,
         id: BytesN<32>,
         additional_usages: u32,
         payment_token: Address,
         payer: Address,
-    ) {
-        autoshare_logic::topup_subscription(env, id, additional_usages, payment_token, payer)
-            .unwrap();
+        plan_duration: Option<u64>,
+    ) -> Result<(), Error> {
+        autoshare_logic::topup_subscription(
+            env,
+            id,
+            additional_usages,
+            payment_token,
+            payer,
+            plan_duration,
+        )
+    }
+
+    /// Sets the price of a time plan in a token, or removes it when `price` is
+    /// `None` (admin or FeeManager).
+    pub fn set_subscription_plan(
+        env: Env,
+        caller: Address,
+        token: Address,
+        duration: u64,
+        price: Option<i128>,
+    ) -> Result<(), Error> {
+        autoshare_logic::set_subscription_plan(env, caller, token, duration, price)
+    }
+
+    /// Returns the price in a token of the time plan lasting `duration` seconds.
+    pub fn get_subscription_plan(env: Env, token: Address, duration: u64) -> Option<i128> {
+        autoshare_logic::get_subscription_plan(env, token, duration)
     }
 
     // ============================================================================
@@ -233,6 +709,26 @@ impl AutoShareContract {
         autoshare_logic::get_group_payment_history(env, id)
     }
 
+    /// Returns up to `limit` payments made by a user starting at position `start`.
+    pub fn get_user_payment_history_page(
+        env: Env,
+        user: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<base::types::PaymentHistoryPage, Error> {
+        autoshare_logic::get_user_payment_history_page(env, user, start, limit)
+    }
+
+    /// Returns up to `limit` payments for a group starting at position `start`.
+    pub fn get_group_payment_history_page(
+        env: Env,
+        id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<base::types::PaymentHistoryPage, Error> {
+        autoshare_logic::get_group_payment_history_page(env, id, start, limit)
+    }
+
     // ============================================================================
     // Distribution History
     // ============================================================================
@@ -253,18 +749,43 @@ impl AutoShareContract {
         autoshare_logic::get_member_distributions(env, member)
     }
 
+    /// Returns up to `limit` distributions of a group starting at position `start`.
+    pub fn get_group_distributions_page(
+        env: Env,
+        id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<base::types::DistributionHistoryPage, Error> {
+        autoshare_logic::get_group_distributions_page(env, id, start, limit)
+    }
+
+    /// Returns up to `limit` distributions received by a member starting at position `start`.
+    pub fn get_member_distributions_page(
+        env: Env,
+        member: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<base::types::DistributionHistoryPage, Error> {
+        autoshare_logic::get_member_distributions_page(env, member, start, limit)
+    }
+
     // ============================================================================
     // Usage Tracking
     // ============================================================================
 
     /// Returns the remaining usages for a group.
-    pub fn get_remaining_usages(env: Env, id: BytesN<32>) -> u32 {
-        autoshare_logic::get_remaining_usages(env, id).unwrap()
+    pub fn get_remaining_usages(env: Env, id: BytesN<32>) -> Result<u32, Error> {
+        autoshare_logic::get_remaining_usages(env, id)
+    }
+
+    /// Returns when a group's time subscription ends, or 0 if it never had one.
+    pub fn get_subscription_expiry(env: Env, id: BytesN<32>) -> Result<u64, Error> {
+        autoshare_logic::get_subscription_expiry(env, id)
     }
 
     /// Returns the total usages paid for a group.
-    pub fn get_total_usages_paid(env: Env, id: BytesN<32>) -> u32 {
-        autoshare_logic::get_total_usages_paid(env, id).unwrap()
+    pub fn get_total_usages_paid(env: Env, id: BytesN<32>) -> Result<u32, Error> {
+        autoshare_logic::get_total_usages_paid(env, id)
     }
 }
 
@@ -292,3 +813,59 @@ mod test_utils_test;
 #[cfg(test)]
 #[path = "tests/distribute_test.rs"]
 mod distribute_test;
+
+#[cfg(test)]
+#[path = "tests/claim_test.rs"]
+mod claim_test;
+
+#[cfg(test)]
+#[path = "tests/splitter_test.rs"]
+mod splitter_test;
+
+#[cfg(test)]
+#[path = "tests/pagination_test.rs"]
+mod pagination_test;
+
+#[cfg(test)]
+#[path = "tests/history_test.rs"]
+mod history_test;
+
+#[cfg(test)]
+#[path = "tests/roles_test.rs"]
+mod roles_test;
+
+#[cfg(test)]
+#[path = "tests/council_test.rs"]
+mod council_test;
+
+#[cfg(test)]
+#[path = "tests/timelock_test.rs"]
+mod timelock_test;
+
+#[cfg(test)]
+#[path = "tests/pricing_test.rs"]
+mod pricing_test;
+
+#[cfg(test)]
+#[path = "tests/subscription_test.rs"]
+mod subscription_test;
+
+#[cfg(test)]
+#[path = "tests/protocol_fee_test.rs"]
+mod protocol_fee_test;
+
+#[cfg(test)]
+#[path = "tests/tranche_test.rs"]
+mod tranche_test;
+
+#[cfg(test)]
+#[path = "tests/nested_group_test.rs"]
+mod nested_group_test;
+
+#[cfg(test)]
+#[path = "tests/batch_test.rs"]
+mod batch_test;
+
+#[cfg(test)]
+#[path = "tests/multi_token_test.rs"]
+mod multi_token_test;
@@ -1,21 +1,62 @@
-use crate::base::types::GroupMember;
+use crate::autoshare_logic::DataKey;
+use crate::base::errors::Error;
+use crate::base::types::{
+    AutoShareDetails, DistributionMode, GroupMember, PendingAdmin, TokenAmount,
+};
 use crate::mock_token::{MockToken, MockTokenClient};
-use crate::test_utils::{create_test_group, setup_test_env};
+use crate::test_utils::{
+    accrue_fee_revenue, create_test_group, deploy_autoshare_contract, member, mint_tokens,
+    setup_test_env,
+};
 use crate::{AutoShareContract, AutoShareContractClient};
 
 /*use soroban_sdk::testutils::Events;*/
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String, Vec};
-fn create_helper(
+use soroban_sdk::{
+    contracttype,
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String, Vec,
+};
+/// `GroupMember` as stored before basis points and fixed amounts.
+#[contracttype]
+#[derive(Clone)]
+struct LegacyGroupMember {
+    address: Address,
+    percentage: u32,
+}
+
+/// `AutoShareDetails` holding members in the legacy layout.
+#[contracttype]
+struct LegacyAutoShareDetails {
+    id: BytesN<32>,
+    name: String,
+    creator: Address,
+    usage_count: u32,
+    total_usages_paid: u32,
+    members: Vec<LegacyGroupMember>,
+    is_active: bool,
+}
+
+fn create_empty_group(
     client: &AutoShareContractClient,
     id: &BytesN<32>,
     name: &String,
     creator: &Address,
-    members: &Vec<GroupMember>,
     test_env: &crate::test_utils::TestEnv,
 ) {
     let token = test_env.mock_tokens.get(0).unwrap().clone();
     crate::test_utils::mint_tokens(&test_env.env, &token, creator, 10000000);
-    client.create(id, name, creator, &1u32, &token);
+    client.create(id, name, creator, &1u32, &token, &None);
+}
+
+fn create_helper(
+    client: &AutoShareContractClient,
+    id: &BytesN<32>,
+    name: &String,
+    creator: &Address,
+    members: &Vec<GroupMember>,
+    test_env: &crate::test_utils::TestEnv,
+) {
+    create_empty_group(client, id, name, creator, test_env);
     client.update_members(id, creator, members);
 }
 
@@ -30,11 +71,17 @@ fn test_create_and_get_success() {
     let member2 = Address::generate(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 60,
+        percentage: 6000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 40,
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let token = test_env.mock_tokens.get(0).unwrap().clone();
     let name = String::from_str(&test_env.env, "Test Group");
@@ -57,15 +104,14 @@ fn test_create_and_get_success() {
     // Check specific member values
     let m1 = result.members.get(0).unwrap();
     assert_eq!(m1.address, member1);
-    assert_eq!(m1.percentage, 60);
+    assert_eq!(m1.percentage, 6000);
 
     let m2 = result.members.get(1).unwrap();
     assert_eq!(m2.address, member2);
-    assert_eq!(m2.percentage, 40);
+    assert_eq!(m2.percentage, 4000);
 }
 
 #[test]
-#[should_panic]
 fn test_duplicate_id_fails() {
     let test_env = setup_test_env();
 
@@ -73,12 +119,14 @@ fn test_duplicate_id_fails() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let token = test_env.mock_tokens.get(0).unwrap().clone();
 
-    // Create group with usages=1 twice
-    create_test_group(
+    let id = create_test_group(
         &test_env.env,
         &test_env.autoshare_contract,
         &creator,
@@ -86,24 +134,23 @@ fn test_duplicate_id_fails() {
         1,
         &token,
     );
-    create_test_group(
-        &test_env.env,
-        &test_env.autoshare_contract,
-        &creator,
-        &members,
-        1,
-        &token,
+
+    // Creating again with the same id fails
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+    let name = String::from_str(&test_env.env, "Test Group");
+    assert_eq!(
+        client.try_create(&id, &name, &creator, &1u32, &token, &None),
+        Err(Ok(Error::AlreadyExists))
     );
 }
 
 #[test]
-#[should_panic]
 fn test_get_non_existent_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
 
     let id = BytesN::from_array(&test_env.env, &[9u8; 32]);
-    client.get(&id);
+    assert_eq!(client.try_get(&id), Err(Ok(Error::NotFound)));
 }
 
 #[test]
@@ -125,7 +172,10 @@ fn test_get_all_groups_multiple() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let token = test_env.mock_tokens.get(0).unwrap().clone();
 
@@ -181,7 +231,10 @@ fn test_get_groups_by_creator_multiple() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let token = test_env.mock_tokens.get(0).unwrap().clone();
 
@@ -217,7 +270,6 @@ fn test_get_groups_by_creator_multiple() {
 }
 
 #[test]
-#[should_panic] // InvalidTotalPercentage
 fn test_create_fails_invalid_percentage() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -229,14 +281,20 @@ fn test_create_fails_invalid_percentage() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 50, // Sum = 50 != 100
+        percentage: 5000, // Sum = 5000 != 10_000
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
-    create_helper(&client, &id, &name, &creator, &members, &test_env);
+    create_empty_group(&client, &id, &name, &creator, &test_env);
+    assert_eq!(
+        client.try_update_members(&id, &creator, &members),
+        Err(Ok(Error::InvalidTotalPercentage))
+    );
 }
 
 #[test]
-#[should_panic] // EmptyMembers
 fn test_create_fails_empty_members() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -247,11 +305,14 @@ fn test_create_fails_empty_members() {
 
     let members = Vec::new(&test_env.env);
 
-    create_helper(&client, &id, &name, &creator, &members, &test_env);
+    create_empty_group(&client, &id, &name, &creator, &test_env);
+    assert_eq!(
+        client.try_update_members(&id, &creator, &members),
+        Err(Ok(Error::EmptyMembers))
+    );
 }
 
 #[test]
-#[should_panic] // DuplicateMember
 fn test_create_fails_duplicate_member() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -264,14 +325,24 @@ fn test_create_fails_duplicate_member() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member_summary.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member_summary, // Duplicate
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
-    create_helper(&client, &id, &name, &creator, &members, &test_env);
+    create_empty_group(&client, &id, &name, &creator, &test_env);
+    assert_eq!(
+        client.try_update_members(&id, &creator, &members),
+        Err(Ok(Error::DuplicateMember))
+    );
 }
 
 #[test]
@@ -287,7 +358,10 @@ fn test_update_members_success() {
     let mut initial_members = Vec::new(&test_env.env);
     initial_members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &initial_members, &test_env);
@@ -301,11 +375,17 @@ fn test_update_members_success() {
     let mut new_members = Vec::new(&test_env.env);
     new_members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     new_members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     client.update_members(&id, &creator, &new_members);
@@ -313,12 +393,11 @@ fn test_update_members_success() {
     // Verify update
     let updated_res = client.get(&id);
     assert_eq!(updated_res.members.len(), 2);
-    assert_eq!(updated_res.members.get(0).unwrap().percentage, 50);
+    assert_eq!(updated_res.members.get(0).unwrap().percentage, 5000);
     assert_eq!(updated_res.members.get(1).unwrap().address, member2);
 }
 
 #[test]
-#[should_panic] // NotAuthorized
 fn test_update_members_unauthorized() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -330,17 +409,22 @@ fn test_update_members_unauthorized() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     let other_user = Address::generate(&test_env.env);
-    client.update_members(&id, &other_user, &members);
+    assert_eq!(
+        client.try_update_members(&id, &other_user, &members),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 #[test]
-#[should_panic] // InvalidTotalPercentage
 fn test_update_members_invalid_percentage() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -352,7 +436,10 @@ fn test_update_members_invalid_percentage() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -360,10 +447,16 @@ fn test_update_members_invalid_percentage() {
     let mut bad_members = Vec::new(&test_env.env);
     bad_members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 90,
+        percentage: 9000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
-    client.update_members(&id, &creator, &bad_members);
+    assert_eq!(
+        client.try_update_members(&id, &creator, &bad_members),
+        Err(Ok(Error::InvalidTotalPercentage))
+    );
 }
 
 #[test]
@@ -380,7 +473,10 @@ fn test_is_group_member() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -399,7 +495,10 @@ fn test_is_group_member_false() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let token = test_env.mock_tokens.get(0).unwrap().clone();
 
@@ -426,7 +525,10 @@ fn test_is_group_member_true() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let token = test_env.mock_tokens.get(0).unwrap().clone();
 
@@ -444,7 +546,6 @@ fn test_is_group_member_true() {
 }
 
 #[test]
-#[should_panic]
 fn test_is_group_member_non_existent_group() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -452,7 +553,10 @@ fn test_is_group_member_non_existent_group() {
     let member = test_env.users.get(0).unwrap().clone();
     let id = BytesN::from_array(&test_env.env, &[99u8; 32]);
 
-    client.is_group_member(&id, &member);
+    assert_eq!(
+        client.try_is_group_member(&id, &member),
+        Err(Ok(Error::NotFound))
+    );
 }
 
 #[test]
@@ -468,15 +572,24 @@ fn test_get_group_members_multiple() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 40,
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 30,
+        percentage: 3000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member3.clone(),
-        percentage: 30,
+        percentage: 3000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     let token = test_env.mock_tokens.get(0).unwrap().clone();
@@ -493,11 +606,11 @@ fn test_get_group_members_multiple() {
     let members_res = client.get_group_members(&id);
     assert_eq!(members_res.len(), 3);
     assert_eq!(members_res.get(0).unwrap().address, member1);
-    assert_eq!(members_res.get(0).unwrap().percentage, 40);
+    assert_eq!(members_res.get(0).unwrap().percentage, 4000);
     assert_eq!(members_res.get(1).unwrap().address, member2);
-    assert_eq!(members_res.get(1).unwrap().percentage, 30);
+    assert_eq!(members_res.get(1).unwrap().percentage, 3000);
     assert_eq!(members_res.get(2).unwrap().address, member3);
-    assert_eq!(members_res.get(2).unwrap().percentage, 30);
+    assert_eq!(members_res.get(2).unwrap().percentage, 3000);
 }
 
 #[test]
@@ -513,7 +626,10 @@ fn test_get_group_members_empty() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -523,13 +639,147 @@ fn test_get_group_members_empty() {
 }
 
 #[test]
-#[should_panic]
 fn test_get_group_members_non_existent_group() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
 
     let id = BytesN::from_array(&test_env.env, &[99u8; 32]);
-    client.get_group_members(&id);
+    assert_eq!(client.try_get_group_members(&id), Err(Ok(Error::NotFound)));
+}
+
+#[test]
+fn test_migrate_member_splits_rescales_legacy_groups() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let member1 = Address::generate(&test_env.env);
+    let member2 = Address::generate(&test_env.env);
+    let mut members = Vec::new(&test_env.env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 6000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let id = create_test_group(
+        &test_env.env,
+        &test_env.autoshare_contract,
+        &creator,
+        &members,
+        1,
+        &token,
+    );
+
+    // Store the group as it was written before basis points and fixed amounts
+    let mut legacy_members = Vec::new(&test_env.env);
+    legacy_members.push_back(LegacyGroupMember {
+        address: member1.clone(),
+        percentage: 60,
+    });
+    legacy_members.push_back(LegacyGroupMember {
+        address: member2.clone(),
+        percentage: 40,
+    });
+    test_env.env.as_contract(&test_env.autoshare_contract, || {
+        let storage = test_env.env.storage().persistent();
+        let key = DataKey::AutoShare(id.clone());
+        let details: AutoShareDetails = storage.get(&key).unwrap();
+        let legacy = LegacyAutoShareDetails {
+            id: details.id,
+            name: details.name,
+            creator: details.creator,
+            usage_count: details.usage_count,
+            total_usages_paid: details.total_usages_paid,
+            members: legacy_members.clone(),
+            is_active: details.is_active,
+        };
+        storage.set(&key, &legacy);
+        storage.set(&DataKey::GroupMembers(id.clone()), &legacy_members);
+    });
+
+    // Legacy groups are readable before they are migrated
+    let legacy = client.get(&id);
+    assert_eq!(legacy.members.get(0).unwrap().percentage, 60);
+    assert_eq!(legacy.members.get(1).unwrap().fixed_token, None);
+    assert_eq!(client.get_group_members(&id).get(1).unwrap().group, None);
+
+    let mut ids = Vec::new(&test_env.env);
+    ids.push_back(id.clone());
+    assert_eq!(client.migrate_member_splits(&test_env.admin, &ids), 1);
+
+    let migrated = client.get_group_members(&id);
+    assert_eq!(migrated.get(0).unwrap().percentage, 6000);
+    assert_eq!(migrated.get(1).unwrap().percentage, 4000);
+
+    // Running it again leaves basis-point groups untouched
+    assert_eq!(client.migrate_member_splits(&test_env.admin, &ids), 0);
+    assert_eq!(
+        client.get_group_members(&id).get(0).unwrap().percentage,
+        6000
+    );
+
+    // Migration rewrites both entries in the current layout
+    test_env.env.as_contract(&test_env.autoshare_contract, || {
+        let storage = test_env.env.storage().persistent();
+        let details: AutoShareDetails = storage.get(&DataKey::AutoShare(id.clone())).unwrap();
+        assert_eq!(details.members, migrated);
+        let members: Vec<GroupMember> = storage.get(&DataKey::GroupMembers(id.clone())).unwrap();
+        assert_eq!(members, migrated);
+    });
+}
+
+#[test]
+fn test_migrate_member_splits_skips_basis_point_groups_summing_to_100() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap();
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let (major, minor) = (Address::generate(env), Address::generate(env));
+
+    let mut members = Vec::new(env);
+    members.push_back(member(&major, 9900));
+    members.push_back(member(&minor, 100));
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &members,
+        1,
+        &token,
+    );
+    client.remove_group_member(&id, &creator, &major);
+
+    let mut ids = Vec::new(env);
+    ids.push_back(id.clone());
+    assert_eq!(client.migrate_member_splits(&test_env.admin, &ids), 0);
+    assert_eq!(
+        client.get_group_members(&id).get(0).unwrap().percentage,
+        100
+    );
+}
+
+#[test]
+fn test_migrate_member_splits_non_admin_fails() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let non_admin = Address::generate(&test_env.env);
+    let ids = Vec::new(&test_env.env);
+    assert_eq!(
+        client.try_migrate_member_splits(&non_admin, &ids),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 // ============================================
@@ -551,11 +801,17 @@ fn test_add_group_member_success() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -564,17 +820,26 @@ fn test_add_group_member_success() {
     let mut updated_members = Vec::new(&test_env.env);
     updated_members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     updated_members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     // Add a placeholder third member with 34% to make 100%
     let placeholder = Address::generate(&test_env.env);
     updated_members.push_back(GroupMember {
         address: placeholder.clone(),
-        percentage: 34,
+        percentage: 3400,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     client.update_members(&id, &creator, &updated_members);
 
@@ -582,16 +847,25 @@ fn test_add_group_member_success() {
     let mut final_members_vec = Vec::new(&test_env.env);
     final_members_vec.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     final_members_vec.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let member3 = Address::generate(&test_env.env);
     final_members_vec.push_back(GroupMember {
         address: member3.clone(),
-        percentage: 34,
+        percentage: 3400,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     client.update_members(&id, &creator, &final_members_vec);
 
@@ -599,11 +873,10 @@ fn test_add_group_member_success() {
     let final_members = client.get_group_members(&id);
     assert_eq!(final_members.len(), 3);
     assert_eq!(final_members.get(2).unwrap().address, member3);
-    assert_eq!(final_members.get(2).unwrap().percentage, 34);
+    assert_eq!(final_members.get(2).unwrap().percentage, 3400);
 }
 
 #[test]
-#[should_panic] // AlreadyExists
 fn test_add_duplicate_member() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -616,17 +889,22 @@ fn test_add_duplicate_member() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     // Try to add the same member again - should fail
-    client.add_group_member(&id, &creator, &member1, &50);
+    assert_eq!(
+        client.try_add_group_member(&id, &creator, &member1, &5000),
+        Err(Ok(Error::AlreadyExists))
+    );
 }
 
 #[test]
-#[should_panic] // NotFound
 fn test_add_member_to_non_existent_group() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -635,11 +913,13 @@ fn test_add_member_to_non_existent_group() {
     let member = Address::generate(&test_env.env);
 
     let caller = Address::generate(&test_env.env);
-    client.add_group_member(&id, &caller, &member, &50);
+    assert_eq!(
+        client.try_add_group_member(&id, &caller, &member, &5000),
+        Err(Ok(Error::NotFound))
+    );
 }
 
 #[test]
-#[should_panic] // InvalidTotalPercentage
 fn test_add_member_invalid_total_percentage() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -653,14 +933,20 @@ fn test_add_member_invalid_total_percentage() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     // Try to add another member with 50% (total would be 150%) - should fail
     let member2 = Address::generate(&test_env.env);
-    client.add_group_member(&id, &creator, &member2, &50);
+    assert_eq!(
+        client.try_add_group_member(&id, &creator, &member2, &5000),
+        Err(Ok(Error::InvalidTotalPercentage))
+    );
 }
 
 #[test]
@@ -677,7 +963,10 @@ fn test_add_multiple_members_sequentially() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -686,28 +975,40 @@ fn test_add_multiple_members_sequentially() {
     let mut updated_members = Vec::new(&test_env.env);
     updated_members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 25,
+        percentage: 2500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     // Add second member with 25% to make 50%
     let member2 = Address::generate(&test_env.env);
     updated_members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 25,
+        percentage: 2500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     // Add third member with 25% to make 75%
     let member3 = Address::generate(&test_env.env);
     updated_members.push_back(GroupMember {
         address: member3.clone(),
-        percentage: 25,
+        percentage: 2500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     // Add fourth member with 25% to make 100%
     let member4 = Address::generate(&test_env.env);
     updated_members.push_back(GroupMember {
         address: member4.clone(),
-        percentage: 25,
+        percentage: 2500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     client.update_members(&id, &creator, &updated_members);
@@ -721,7 +1022,7 @@ fn test_add_multiple_members_sequentially() {
     for member in final_members.iter() {
         total += member.percentage;
     }
-    assert_eq!(total, 100);
+    assert_eq!(total, 10_000);
 }
 
 #[test]
@@ -739,11 +1040,17 @@ fn test_add_member_to_inactive_group() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -752,16 +1059,25 @@ fn test_add_member_to_inactive_group() {
     let mut updated_members = Vec::new(&test_env.env);
     updated_members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     updated_members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let placeholder = Address::generate(&test_env.env);
     updated_members.push_back(GroupMember {
         address: placeholder.clone(),
-        percentage: 34,
+        percentage: 3400,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     client.update_members(&id, &creator, &updated_members);
 
@@ -773,16 +1089,25 @@ fn test_add_member_to_inactive_group() {
     let mut final_members_vec = Vec::new(&test_env.env);
     final_members_vec.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     final_members_vec.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 33,
+        percentage: 3300,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     let member3 = Address::generate(&test_env.env);
     final_members_vec.push_back(GroupMember {
         address: member3.clone(),
-        percentage: 34,
+        percentage: 3400,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     // Reactivate to update, then deactivate again
@@ -815,15 +1140,24 @@ fn test_remove_group_member_success() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 40,
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 35,
+        percentage: 3500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member3.clone(),
-        percentage: 25,
+        percentage: 2500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -840,7 +1174,6 @@ fn test_remove_group_member_success() {
 }
 
 #[test]
-#[should_panic] // Unauthorized
 fn test_remove_group_member_unauthorized() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -854,21 +1187,29 @@ fn test_remove_group_member_unauthorized() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     let other_user = Address::generate(&test_env.env);
-    client.remove_group_member(&id, &other_user, &member2);
+    assert_eq!(
+        client.try_remove_group_member(&id, &other_user, &member2),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 #[test]
-#[should_panic] // GroupInactive
 fn test_remove_group_member_inactive() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -882,21 +1223,29 @@ fn test_remove_group_member_inactive() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
     client.deactivate_group(&id, &creator);
 
-    client.remove_group_member(&id, &creator, &member2);
+    assert_eq!(
+        client.try_remove_group_member(&id, &creator, &member2),
+        Err(Ok(Error::GroupInactive))
+    );
 }
 
 #[test]
-#[should_panic] // MemberNotFound
 fn test_remove_group_member_not_found() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -910,21 +1259,29 @@ fn test_remove_group_member_not_found() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     let not_in_group = Address::generate(&test_env.env);
-    client.remove_group_member(&id, &creator, &not_in_group);
+    assert_eq!(
+        client.try_remove_group_member(&id, &creator, &not_in_group),
+        Err(Ok(Error::MemberNotFound))
+    );
 }
 
 #[test]
-#[should_panic] // ContractPaused
 fn test_remove_group_member_paused() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -939,17 +1296,26 @@ fn test_remove_group_member_paused() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
     client.pause(&admin);
 
-    client.remove_group_member(&id, &creator, &member2);
+    assert_eq!(
+        client.try_remove_group_member(&id, &creator, &member2),
+        Err(Ok(Error::ContractPaused))
+    );
 }
 
 #[test]
@@ -966,11 +1332,17 @@ fn test_remove_group_member_then_update_members() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -979,19 +1351,22 @@ fn test_remove_group_member_then_update_members() {
     let after_remove = client.get_group_members(&id);
     assert_eq!(after_remove.len(), 1);
     assert_eq!(after_remove.get(0).unwrap().address, member1);
-    assert_eq!(after_remove.get(0).unwrap().percentage, 50);
+    assert_eq!(after_remove.get(0).unwrap().percentage, 5000);
 
     let mut single_member = Vec::new(&test_env.env);
     single_member.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     client.update_members(&id, &creator, &single_member);
 
     let final_members = client.get_group_members(&id);
     assert_eq!(final_members.len(), 1);
     assert_eq!(final_members.get(0).unwrap().address, member1);
-    assert_eq!(final_members.get(0).unwrap().percentage, 100);
+    assert_eq!(final_members.get(0).unwrap().percentage, 10000);
 }
 
 // ============================================
@@ -1010,7 +1385,10 @@ fn test_group_created_as_active() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1031,7 +1409,10 @@ fn test_creator_can_deactivate_group() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1055,7 +1436,10 @@ fn test_creator_can_activate_group() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1072,7 +1456,6 @@ fn test_creator_can_activate_group() {
 }
 
 #[test]
-#[should_panic] // GroupInactive
 fn test_updating_inactive_group_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1084,7 +1467,10 @@ fn test_updating_inactive_group_fails() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1096,14 +1482,23 @@ fn test_updating_inactive_group_fails() {
     let mut new_members = Vec::new(&test_env.env);
     new_members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     new_members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
-    client.update_members(&id, &creator, &new_members);
+    assert_eq!(
+        client.try_update_members(&id, &creator, &new_members),
+        Err(Ok(Error::GroupInactive))
+    );
 }
 
 #[test]
@@ -1119,7 +1514,10 @@ fn test_viewing_inactive_group_works() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1135,7 +1533,6 @@ fn test_viewing_inactive_group_works() {
 }
 
 #[test]
-#[should_panic] // NotAuthorized
 fn test_non_creator_cannot_deactivate() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1148,17 +1545,22 @@ fn test_non_creator_cannot_deactivate() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     // Try to deactivate as non-creator - should fail
-    client.deactivate_group(&id, &other_user);
+    assert_eq!(
+        client.try_deactivate_group(&id, &other_user),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 #[test]
-#[should_panic] // NotAuthorized
 fn test_non_creator_cannot_activate() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1171,7 +1573,10 @@ fn test_non_creator_cannot_activate() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1180,11 +1585,13 @@ fn test_non_creator_cannot_activate() {
     client.deactivate_group(&id, &creator);
 
     // Try to activate as non-creator - should fail
-    client.activate_group(&id, &other_user);
+    assert_eq!(
+        client.try_activate_group(&id, &other_user),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 #[test]
-#[should_panic] // GroupAlreadyInactive
 fn test_deactivating_already_inactive_group_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1196,7 +1603,10 @@ fn test_deactivating_already_inactive_group_fails() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1205,11 +1615,13 @@ fn test_deactivating_already_inactive_group_fails() {
     client.deactivate_group(&id, &creator);
 
     // Try to deactivate again - should fail
-    client.deactivate_group(&id, &creator);
+    assert_eq!(
+        client.try_deactivate_group(&id, &creator),
+        Err(Ok(Error::GroupAlreadyInactive))
+    );
 }
 
 #[test]
-#[should_panic] // GroupAlreadyActive
 fn test_activating_already_active_group_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1221,17 +1633,22 @@ fn test_activating_already_active_group_fails() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
 
     // Group is already active by default, try to activate again - should fail
-    client.activate_group(&id, &creator);
+    assert_eq!(
+        client.try_activate_group(&id, &creator),
+        Err(Ok(Error::GroupAlreadyActive))
+    );
 }
 
 #[test]
-#[should_panic] // NotFound
 fn test_status_change_on_nonexistent_group_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1240,11 +1657,13 @@ fn test_status_change_on_nonexistent_group_fails() {
     let id = BytesN::from_array(&test_env.env, &[99u8; 32]); // Non-existent group
 
     // Try to deactivate non-existent group - should fail
-    client.deactivate_group(&id, &creator);
+    assert_eq!(
+        client.try_deactivate_group(&id, &creator),
+        Err(Ok(Error::NotFound))
+    );
 }
 
 #[test]
-#[should_panic] // NotFound
 fn test_is_group_active_on_nonexistent_group_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
@@ -1252,7 +1671,7 @@ fn test_is_group_active_on_nonexistent_group_fails() {
     let id = BytesN::from_array(&test_env.env, &[99u8; 32]); // Non-existent group
 
     // Try to check status of non-existent group - should fail
-    client.is_group_active(&id);
+    assert_eq!(client.try_is_group_active(&id), Err(Ok(Error::NotFound)));
 }
 
 #[test]
@@ -1269,7 +1688,10 @@ fn test_get_all_groups_includes_inactive() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: Address::generate(&test_env.env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     // Create two groups
@@ -1304,7 +1726,10 @@ fn test_is_group_member_works_on_inactive_group() {
     let mut members = Vec::new(&test_env.env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     create_helper(&client, &id, &name, &creator, &members, &test_env);
@@ -1316,6 +1741,301 @@ fn test_is_group_member_works_on_inactive_group() {
     assert!(client.is_group_member(&id, &member1));
 }
 
+#[test]
+fn test_get_groups_by_member() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id1 = BytesN::from_array(&test_env.env, &[1u8; 32]);
+    let id2 = BytesN::from_array(&test_env.env, &[2u8; 32]);
+    let name = String::from_str(&test_env.env, "Member Index");
+
+    let shared = Address::generate(&test_env.env);
+    let other = Address::generate(&test_env.env);
+    let mut members1 = Vec::new(&test_env.env);
+    members1.push_back(GroupMember {
+        address: shared.clone(),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let mut members2 = Vec::new(&test_env.env);
+    members2.push_back(GroupMember {
+        address: shared.clone(),
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members2.push_back(GroupMember {
+        address: other.clone(),
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    create_helper(&client, &id1, &name, &creator, &members1, &test_env);
+    create_helper(&client, &id2, &name, &creator, &members2, &test_env);
+
+    let groups = client.get_groups_by_member(&shared);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups.get(0).unwrap().id, id1);
+    assert_eq!(groups.get(1).unwrap().id, id2);
+
+    let groups = client.get_groups_by_member(&other);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups.get(0).unwrap().id, id2);
+
+    assert_eq!(
+        client
+            .get_groups_by_member(&Address::generate(&test_env.env))
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_member_index_follows_add_and_remove() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = BytesN::from_array(&test_env.env, &[1u8; 32]);
+    let name = String::from_str(&test_env.env, "Member Index");
+
+    let member1 = Address::generate(&test_env.env);
+    let member2 = Address::generate(&test_env.env);
+    let mut members = Vec::new(&test_env.env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 6000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    create_helper(&client, &id, &name, &creator, &members, &test_env);
+
+    client.remove_group_member(&id, &creator, &member2);
+    assert_eq!(client.get_groups_by_member(&member2).len(), 0);
+    assert_eq!(client.get_groups_by_member(&member1).len(), 1);
+
+    client.add_group_member(&id, &creator, &member2, &4000);
+    let groups = client.get_groups_by_member(&member2);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups.get(0).unwrap().id, id);
+}
+
+#[test]
+fn test_member_index_follows_update_members() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = BytesN::from_array(&test_env.env, &[1u8; 32]);
+    let name = String::from_str(&test_env.env, "Member Index");
+
+    let kept = Address::generate(&test_env.env);
+    let dropped = Address::generate(&test_env.env);
+    let added = Address::generate(&test_env.env);
+    let mut members = Vec::new(&test_env.env);
+    members.push_back(GroupMember {
+        address: kept.clone(),
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: dropped.clone(),
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    create_helper(&client, &id, &name, &creator, &members, &test_env);
+
+    let mut new_members = Vec::new(&test_env.env);
+    new_members.push_back(GroupMember {
+        address: kept.clone(),
+        percentage: 7000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    new_members.push_back(GroupMember {
+        address: added.clone(),
+        percentage: 3000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    client.update_members(&id, &creator, &new_members);
+
+    assert_eq!(client.get_groups_by_member(&kept).len(), 1);
+    assert_eq!(client.get_groups_by_member(&dropped).len(), 0);
+    assert_eq!(client.get_groups_by_member(&added).len(), 1);
+}
+
+#[test]
+fn test_delete_group_removes_it_from_member_index() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = BytesN::from_array(&test_env.env, &[1u8; 32]);
+    let name = String::from_str(&test_env.env, "Member Index");
+
+    let member = Address::generate(&test_env.env);
+    let mut members = Vec::new(&test_env.env);
+    members.push_back(GroupMember {
+        address: member.clone(),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    create_helper(&client, &id, &name, &creator, &members, &test_env);
+
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+
+    assert_eq!(client.get_groups_by_member(&member).len(), 0);
+}
+
+#[test]
+fn test_delete_group_refunds_remaining_usages() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let token_client = MockTokenClient::new(env, &token);
+
+    let creator = test_env.users.get(0).unwrap();
+    let payer = test_env.users.get(1).unwrap();
+    let mut members = Vec::new(env);
+    members.push_back(GroupMember {
+        address: Address::generate(env),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let id = create_test_group(env, contract, &creator, &members, 5, &token);
+
+    // Top up at a higher fee; the refund uses the price actually paid
+    client.set_usage_fee(&token, &20, &test_env.admin);
+    mint_tokens(env, &token, &payer, 60);
+    client.topup_subscription(&id, &3, &token, &payer, &None);
+    assert_eq!(client.get_fee_revenue(&token), 0);
+
+    // Spent usages come out of the oldest purchase and become revenue
+    mint_tokens(env, &token, &creator, 200);
+    client.distribute(&id, &token, &100, &creator);
+    client.distribute(&id, &token, &100, &creator);
+    assert_eq!(client.get_fee_revenue(&token), 20);
+
+    // Withdrawing all revenue leaves the unspent usages refundable
+    let treasury = Address::generate(env);
+    client.withdraw(&test_env.admin, &token, &20, &treasury);
+
+    let creator_before = token_client.balance(&creator);
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+
+    assert_eq!(token_client.balance(&creator), creator_before + 30);
+    assert_eq!(token_client.balance(&payer), 60);
+    assert_eq!(token_client.balance(&treasury), 20);
+    assert_eq!(client.get_fee_revenue(&token), 0);
+
+    let history = client.get_group_payment_history(&id);
+    assert_eq!(history.len(), 4);
+    let creator_refund = history.get(2).unwrap();
+    assert_eq!(creator_refund.user, creator);
+    assert_eq!(creator_refund.usages_purchased, 3);
+    assert_eq!(creator_refund.amount_paid, -30);
+    let payer_refund = history.get(3).unwrap();
+    assert_eq!(payer_refund.user, payer);
+    assert_eq!(payer_refund.usages_purchased, 3);
+    assert_eq!(payer_refund.amount_paid, -60);
+}
+
+#[test]
+fn test_delete_group_forfeits_untracked_usages() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    let creator = test_env.users.get(0).unwrap();
+    let id = create_test_group(env, contract, &creator, &Vec::new(env), 5, &token);
+
+    // Simulate a group paid for before usage lots were tracked, when the
+    // whole purchase was credited to the fee revenue
+    env.as_contract(contract, || {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::GroupUsageLots(id.clone()));
+        env.storage()
+            .persistent()
+            .set(&DataKey::FeeRevenue(token.clone()), &50i128);
+    });
+    client.topup_subscription(&id, &2, &token, &creator, &None);
+
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+
+    // Only the tracked top-up is refunded
+    assert_eq!(client.get_fee_revenue(&token), 50);
+    assert_eq!(client.get_group_payment_history(&id).len(), 3);
+}
+
+#[test]
+fn test_reindex_groups_backfills_member_index() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = BytesN::from_array(&test_env.env, &[1u8; 32]);
+    let name = String::from_str(&test_env.env, "Member Index");
+
+    let member = Address::generate(&test_env.env);
+    let mut members = Vec::new(&test_env.env);
+    members.push_back(GroupMember {
+        address: member.clone(),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    create_helper(&client, &id, &name, &creator, &members, &test_env);
+
+    // Simulate a group created before the member index existed
+    test_env.env.as_contract(&test_env.autoshare_contract, || {
+        test_env
+            .env
+            .storage()
+            .persistent()
+            .remove(&DataKey::MemberGroups(member.clone()));
+    });
+    assert_eq!(client.get_groups_by_member(&member).len(), 0);
+
+    client.reindex_groups(&test_env.admin, &0, &10);
+    assert_eq!(client.get_groups_by_member(&member).len(), 1);
+}
+
 // =====================
 // Admin Management Tests
 // =====================
@@ -1324,11 +2044,10 @@ fn test_is_group_member_works_on_inactive_group() {
 fn test_initialize_with_admin() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let retrieved_admin = client.get_admin();
     assert_eq!(retrieved_admin, admin);
@@ -1338,61 +2057,218 @@ fn test_initialize_with_admin() {
 fn test_get_admin() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let result = client.get_admin();
     assert_eq!(result, admin);
 }
 
 #[test]
-fn test_transfer_admin() {
+fn test_propose_and_accept_admin() {
     let env = Env::default();
     env.mock_all_auths();
+
+    let old_admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let contract_id = deploy_autoshare_contract(&env, &old_admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&old_admin, &new_admin, &None);
+
+    // Nothing changes until the new admin accepts
+    assert_eq!(client.get_admin(), old_admin);
+    assert_eq!(
+        client.get_pending_admin(),
+        Some(PendingAdmin {
+            new_admin: new_admin.clone(),
+            expires_at: None,
+        })
+    );
+
+    client.accept_admin(&new_admin);
+
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_propose_admin_unauthorized() {
+    let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
+
+    let admin = Address::generate(&env);
+    let non_admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let contract_id = deploy_autoshare_contract(&env, &admin);
     let client = AutoShareContractClient::new(&env, &contract_id);
+    assert_eq!(
+        client.try_propose_admin(&non_admin, &new_admin, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+}
 
-    let old_admin = Address::generate(&env);
+#[test]
+fn test_accept_admin_by_other_address_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
     let new_admin = Address::generate(&env);
+    let other = Address::generate(&env);
 
-    client.initialize_admin(&old_admin);
-    client.transfer_admin(&old_admin, &new_admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&admin, &new_admin, &None);
 
-    let current_admin = client.get_admin();
-    assert_eq!(current_admin, new_admin);
+    assert_eq!(
+        client.try_accept_admin(&other),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.get_admin(), admin);
 }
 
 #[test]
-#[should_panic]
-fn test_transfer_admin_unauthorized() {
+fn test_accept_admin_requires_new_admin_auth() {
     let env = Env::default();
     env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&admin, &new_admin, &None);
+
+    env.set_auths(&[]);
+    assert!(client.try_accept_admin(&new_admin).is_err());
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_accept_admin_without_proposal_fails() {
+    let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
+
+    let admin = Address::generate(&env);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
     let client = AutoShareContractClient::new(&env, &contract_id);
 
+    assert_eq!(
+        client.try_accept_admin(&Address::generate(&env)),
+        Err(Ok(Error::NoPendingAdmin))
+    );
+}
+
+#[test]
+fn test_accept_admin_after_expiry_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
     let admin = Address::generate(&env);
-    let non_admin = Address::generate(&env);
     let new_admin = Address::generate(&env);
 
-    client.initialize_admin(&admin);
-    client.transfer_admin(&non_admin, &new_admin);
+    env.ledger().set_timestamp(1_000);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&admin, &new_admin, &Some(2_000));
+
+    env.ledger().set_timestamp(2_001);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(Error::AdminProposalExpired))
+    );
+    assert_eq!(client.get_admin(), admin);
 }
 
 #[test]
-fn test_admin_can_pause() {
+fn test_accept_admin_before_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    env.ledger().set_timestamp(1_000);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&admin, &new_admin, &Some(2_000));
+
+    env.ledger().set_timestamp(2_000);
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+}
+
+#[test]
+fn test_propose_admin_with_past_expiry_fails() {
     let env = Env::default();
     env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    env.ledger().set_timestamp(1_000);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_propose_admin(&admin, &Address::generate(&env), &Some(1_000)),
+        Err(Ok(Error::InvalidInput))
+    );
+}
+
+#[test]
+fn test_cancel_admin_transfer() {
+    let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let contract_id = deploy_autoshare_contract(&env, &admin);
     let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&admin, &new_admin, &None);
+    client.cancel_admin_transfer(&admin);
+
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(Error::NoPendingAdmin))
+    );
+    assert_eq!(
+        client.try_cancel_admin_transfer(&admin),
+        Err(Ok(Error::NoPendingAdmin))
+    );
+}
+
+#[test]
+fn test_cancel_admin_transfer_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let new_admin = Address::generate(&env);
+
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&admin, &new_admin, &None);
+
+    assert_eq!(
+        client.try_cancel_admin_transfer(&new_admin),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_admin_can_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     client.pause(&admin);
     assert!(client.get_paused_status());
@@ -1403,11 +2279,10 @@ fn test_admin_can_unpause() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     client.pause(&admin);
     assert!(client.get_paused_status());
@@ -1426,11 +2301,9 @@ fn test_get_contract_balance() {
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1456,12 +2329,10 @@ fn test_admin_can_withdraw() {
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1473,8 +2344,10 @@ fn test_admin_can_withdraw() {
         &String::from_str(&env, "TST"),
     );
 
-    // Mint some tokens to the contract
-    token_client.mint(&contract_id, &1000);
+    // Collect fee revenue in the token
+    client.add_supported_token(&token_id, &admin);
+    client.set_usage_fee(&token_id, &10, &admin);
+    accrue_fee_revenue(&env, &contract_id, &token_id, 100);
 
     // Withdraw tokens
     client.withdraw(&admin, &token_id, &500, &recipient);
@@ -1488,19 +2361,16 @@ fn test_admin_can_withdraw() {
 }
 
 #[test]
-#[should_panic]
 fn test_non_admin_cannot_withdraw() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
     let non_admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1515,23 +2385,23 @@ fn test_non_admin_cannot_withdraw() {
     // Mint some tokens to the contract
     token_client.mint(&contract_id, &1000);
 
-    // Try to withdraw as non-admin (should panic)
-    client.withdraw(&non_admin, &token_id, &500, &recipient);
+    // Try to withdraw as non-admin (should fail)
+    assert_eq!(
+        client.try_withdraw(&non_admin, &token_id, &500, &recipient),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_withdraw_insufficient_balance() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1543,26 +2413,130 @@ fn test_withdraw_insufficient_balance() {
         &String::from_str(&env, "TST"),
     );
 
-    // Mint some tokens to the contract
-    token_client.mint(&contract_id, &1000);
+    // Collect fee revenue in the token
+    client.add_supported_token(&token_id, &admin);
+    client.set_usage_fee(&token_id, &10, &admin);
+    accrue_fee_revenue(&env, &contract_id, &token_id, 100);
+
+    // Try to withdraw more than available (should fail)
+    assert_eq!(
+        client.try_withdraw(&admin, &token_id, &1500, &recipient),
+        Err(Ok(Error::InsufficientFeeRevenue))
+    );
+}
+
+#[test]
+fn test_fee_revenue_accrues_as_usages_are_spent() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap();
+    assert_eq!(client.get_fee_revenue(&token), 0);
 
-    // Try to withdraw more than available (should panic)
-    client.withdraw(&admin, &token_id, &1500, &recipient);
+    let mut members = Vec::new(env);
+    members.push_back(GroupMember {
+        address: Address::generate(env),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &members,
+        5,
+        &token,
+    );
+    client.topup_subscription(&id, &3, &token, &creator, &None);
+    // Unspent usages are refundable, so they are not revenue yet
+    assert_eq!(client.get_fee_revenue(&token), 0);
+
+    for _ in 0..3 {
+        client.distribute(&id, &token, &100, &creator);
+    }
+    assert_eq!(client.get_fee_revenue(&token), 30);
+
+    client.withdraw(&test_env.admin, &token, &20, &creator);
+    assert_eq!(client.get_fee_revenue(&token), 10);
+}
+
+#[test]
+fn test_withdraw_cannot_take_unaccrued_balance() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let recipient = test_env.users.get(0).unwrap();
+
+    // Tokens sent directly to the contract are not fee revenue
+    mint_tokens(env, &token, &test_env.autoshare_contract, 1000);
+    assert_eq!(client.get_contract_balance(&token), 1000);
+    assert_eq!(
+        client.try_withdraw(&test_env.admin, &token, &1, &recipient),
+        Err(Ok(Error::InsufficientFeeRevenue))
+    );
+}
+
+#[test]
+fn test_migrate_fee_revenue_recovers_untracked_balance() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap();
+    let member = Address::generate(env);
+
+    // Fees collected before revenue was tracked
+    mint_tokens(env, &token, &test_env.autoshare_contract, 1000);
+
+    // Unspent usages and claimable balances stay owed
+    let mut members = Vec::new(env);
+    members.push_back(GroupMember {
+        address: member.clone(),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &members,
+        5,
+        &token,
+    );
+    client.set_distribution_mode(&id, &creator, &DistributionMode::Claim);
+    client.distribute(&id, &token, &100, &creator);
+    assert_eq!(client.get_fee_revenue(&token), 10);
+
+    assert_eq!(
+        client.try_migrate_fee_revenue(&Address::generate(env), &token),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.migrate_fee_revenue(&test_env.admin, &token), 1000);
+    assert_eq!(client.migrate_fee_revenue(&test_env.admin, &token), 0);
+
+    client.withdraw(&test_env.admin, &token, &1010, &creator);
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+    assert_eq!(client.claim(&member, &token), 100);
+    assert_eq!(client.get_contract_balance(&token), 0);
 }
 
 #[test]
-#[should_panic]
 fn test_withdraw_zero_amount() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1577,23 +2551,23 @@ fn test_withdraw_zero_amount() {
     // Mint some tokens to the contract
     token_client.mint(&contract_id, &1000);
 
-    // Try to withdraw zero amount (should panic)
-    client.withdraw(&admin, &token_id, &0, &recipient);
+    // Try to withdraw zero amount (should fail)
+    assert_eq!(
+        client.try_withdraw(&admin, &token_id, &0, &recipient),
+        Err(Ok(Error::InvalidAmount))
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_withdraw_negative_amount() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1608,8 +2582,11 @@ fn test_withdraw_negative_amount() {
     // Mint some tokens to the contract
     token_client.mint(&contract_id, &1000);
 
-    // Try to withdraw negative amount (should panic)
-    client.withdraw(&admin, &token_id, &-100, &recipient);
+    // Try to withdraw negative amount (should fail)
+    assert_eq!(
+        client.try_withdraw(&admin, &token_id, &-100, &recipient),
+        Err(Ok(Error::InvalidAmount))
+    );
 }
 
 #[test]
@@ -1618,15 +2595,14 @@ fn test_admin_functions_after_transfer() {
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let old_admin = Address::generate(&env);
     let new_admin = Address::generate(&env);
     let recipient = Address::generate(&env);
 
-    client.initialize_admin(&old_admin);
-    client.transfer_admin(&old_admin, &new_admin);
+    let contract_id = deploy_autoshare_contract(&env, &old_admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&old_admin, &new_admin, &None);
+    client.accept_admin(&new_admin);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1638,8 +2614,10 @@ fn test_admin_functions_after_transfer() {
         &String::from_str(&env, "TST"),
     );
 
-    // Mint some tokens to the contract
-    token_client.mint(&contract_id, &1000);
+    // Collect fee revenue in the token
+    client.add_supported_token(&token_id, &new_admin);
+    client.set_usage_fee(&token_id, &10, &new_admin);
+    accrue_fee_revenue(&env, &contract_id, &token_id, 100);
 
     // New admin should be able to withdraw
     client.withdraw(&new_admin, &token_id, &500, &recipient);
@@ -1649,21 +2627,19 @@ fn test_admin_functions_after_transfer() {
 }
 
 #[test]
-#[should_panic]
 fn test_old_admin_cannot_withdraw_after_transfer() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
 
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
-
     let old_admin = Address::generate(&env);
     let new_admin = Address::generate(&env);
     let recipient = Address::generate(&env);
 
-    client.initialize_admin(&old_admin);
-    client.transfer_admin(&old_admin, &new_admin);
+    let contract_id = deploy_autoshare_contract(&env, &old_admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+    client.propose_admin(&old_admin, &new_admin, &None);
+    client.accept_admin(&new_admin);
 
     // Create and initialize token
     let token_id = env.register(MockToken, ());
@@ -1678,8 +2654,11 @@ fn test_old_admin_cannot_withdraw_after_transfer() {
     // Mint some tokens to the contract
     token_client.mint(&contract_id, &1000);
 
-    // Old admin should NOT be able to withdraw (should panic)
-    client.withdraw(&old_admin, &token_id, &500, &recipient);
+    // Old admin should NOT be able to withdraw (should fail)
+    assert_eq!(
+        client.try_withdraw(&old_admin, &token_id, &500, &recipient),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 // ============================================================================
@@ -1691,26 +2670,83 @@ fn test_admin_initialization() {
     let env = Env::default();
     env.mock_all_auths();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
-    // Check default usage fee is set
-    let fee = client.get_usage_fee();
-    assert_eq!(fee, 10u32);
+    // There is no default usage fee
+    assert_eq!(
+        client.try_get_usage_fee(&Address::generate(&env)),
+        Err(Ok(Error::UsageFeeNotSet))
+    );
 
     // Check supported tokens list is empty
     let tokens = client.get_supported_tokens();
     assert_eq!(tokens.len(), 0);
 }
 
+#[test]
+fn test_constructor_sets_fee_and_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token1 = Address::generate(&env);
+    let token2 = Address::generate(&env);
+    let mut usage_fees = Vec::new(&env);
+    for (token, fee) in [(&token1, 25), (&token2, 3_000_000), (&token1, 40)] {
+        usage_fees.push_back(TokenAmount {
+            token: token.clone(),
+            amount: fee,
+        });
+    }
+
+    let contract_id = env.register(AutoShareContract, (&admin, usage_fees));
+    let client = AutoShareContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_usage_fee(&token1), 25);
+    assert_eq!(client.get_usage_fee(&token2), 3_000_000);
+    let supported = client.get_supported_tokens();
+    assert_eq!(supported.len(), 2);
+    assert!(client.is_token_supported(&token1));
+    assert!(client.is_token_supported(&token2));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_constructor_rejects_zero_fee() {
+    let env = Env::default();
+    let mut usage_fees = Vec::new(&env);
+    usage_fees.push_back(TokenAmount {
+        token: Address::generate(&env),
+        amount: 0,
+    });
+    env.register(AutoShareContract, (&Address::generate(&env), usage_fees));
+}
+
+#[test]
+fn test_initialize_admin_after_constructor_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
+
+    let attacker = Address::generate(&env);
+    assert_eq!(
+        client.try_initialize_admin(&attacker),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(client.get_admin(), admin);
+}
+
 #[test]
 fn test_add_and_get_supported_tokens() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
 
     let token_address = test_env.mock_tokens.get(0).unwrap().clone();
     // client.add_supported_token(&token_address, &test_env.admin);
@@ -1725,22 +2761,22 @@ fn test_add_and_get_supported_tokens() {
 }
 
 #[test]
-#[should_panic]
 fn test_add_duplicate_token_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
 
     let token_address = test_env.mock_tokens.get(0).unwrap().clone();
     // client.add_supported_token(&token_address, &test_env.admin);
-    client.add_supported_token(&token_address, &test_env.admin);
+    assert_eq!(
+        client.try_add_supported_token(&token_address, &test_env.admin),
+        Err(Ok(Error::AlreadyExists))
+    );
 }
 
 #[test]
 fn test_remove_supported_token() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
 
     let token_address = test_env.mock_tokens.get(0).unwrap().clone();
     // client.add_supported_token(&token_address, &test_env.admin);
@@ -1753,46 +2789,137 @@ fn test_remove_supported_token() {
 }
 
 #[test]
-#[should_panic]
 fn test_remove_non_existent_token_fails() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
 
     let non_existent_token = Address::generate(&test_env.env);
-    client.remove_supported_token(&non_existent_token, &test_env.admin);
+    assert_eq!(
+        client.try_remove_supported_token(&non_existent_token, &test_env.admin),
+        Err(Ok(Error::NotFound))
+    );
 }
 
 #[test]
 fn test_set_and_get_usage_fee() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
+    let token = test_env.mock_tokens.get(0).unwrap();
 
-    let new_fee = 25u32;
-    client.set_usage_fee(&new_fee, &test_env.admin);
+    let new_fee = 25i128;
+    client.set_usage_fee(&token, &new_fee, &test_env.admin);
 
-    let fee = client.get_usage_fee();
+    let fee = client.get_usage_fee(&token);
     assert_eq!(fee, new_fee);
 }
 
 #[test]
-#[should_panic]
 fn test_non_admin_cannot_set_usage_fee() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
 
+    let token = test_env.mock_tokens.get(0).unwrap();
     let non_admin = Address::generate(&test_env.env);
-    let new_fee = 25u32;
-    client.set_usage_fee(&new_fee, &non_admin);
+    let new_fee = 25i128;
+    assert_eq!(
+        client.try_set_usage_fee(&token, &new_fee, &non_admin),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_usage_fee_is_per_token() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let whole_unit_token = crate::test_utils::deploy_mock_token(
+        env,
+        &String::from_str(env, "Whole Unit"),
+        &String::from_str(env, "WHL"),
+    );
+    client.add_supported_token(&whole_unit_token, &test_env.admin);
+
+    client.set_usage_fee(&token, &1_000_000, &test_env.admin);
+    client.set_usage_fee(&whole_unit_token, &2, &test_env.admin);
+    assert_eq!(client.get_usage_fee(&token), 1_000_000);
+    assert_eq!(client.get_usage_fee(&whole_unit_token), 2);
+
+    // Each group is charged the fee of the token it pays with
+    let creator = test_env.users.get(0).unwrap();
+    mint_tokens(env, &token, &creator, 3_000_000);
+    mint_tokens(env, &whole_unit_token, &creator, 6);
+    let id1 = BytesN::from_array(env, &[1u8; 32]);
+    let id2 = BytesN::from_array(env, &[2u8; 32]);
+    client.create(
+        &id1,
+        &String::from_str(env, "A"),
+        &creator,
+        &3,
+        &token,
+        &None,
+    );
+    client.create(
+        &id2,
+        &String::from_str(env, "B"),
+        &creator,
+        &3,
+        &whole_unit_token,
+        &None,
+    );
+
+    assert_eq!(client.get_contract_balance(&token), 3_000_000);
+    assert_eq!(client.get_contract_balance(&whole_unit_token), 6);
+}
+
+#[test]
+fn test_set_usage_fee_validation() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    assert_eq!(
+        client.try_set_usage_fee(&Address::generate(&test_env.env), &5, &test_env.admin),
+        Err(Ok(Error::UnsupportedToken))
+    );
+    assert_eq!(
+        client.try_set_usage_fee(&token, &0, &test_env.admin),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    // Removing a token drops its fee, so usages cannot be bought in it again
+    // until a new fee is set
+    client.set_usage_fee(&token, &99, &test_env.admin);
+    client.remove_supported_token(&token, &test_env.admin);
+    client.add_supported_token(&token, &test_env.admin);
+    assert_eq!(
+        client.try_get_usage_fee(&token),
+        Err(Ok(Error::UsageFeeNotSet))
+    );
+    assert_eq!(
+        client.try_quote_usages(&token, &1),
+        Err(Ok(Error::UsageFeeNotSet))
+    );
+
+    let creator = test_env.users.get(0).unwrap();
+    mint_tokens(&test_env.env, &token, &creator, 1000);
+    assert_eq!(
+        client.try_create(
+            &BytesN::from_array(&test_env.env, &[1u8; 32]),
+            &String::from_str(&test_env.env, "No Fee"),
+            &creator,
+            &1,
+            &token,
+            &None,
+        ),
+        Err(Ok(Error::UsageFeeNotSet))
+    );
 }
 
 #[test]
 fn test_create_group_with_payment() {
     let test_env = setup_test_env();
     let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
-    client.initialize_admin(&test_env.admin);
 
     let creator = test_env.users.get(0).unwrap().clone();
     let token_address = test_env.mock_tokens.get(0).unwrap().clone();
@@ -1805,7 +2932,7 @@ fn test_create_group_with_payment() {
     let name = String::from_str(&test_env.env, "Paid Group");
     let usage_count = 50u32;
 
-    client.create(&id, &name, &creator, &usage_count, &token_address);
+    client.create(&id, &name, &creator, &usage_count, &token_address, &None);
 
     let details = client.get(&id);
     assert_eq!(details.usage_count, usage_count);
@@ -0,0 +1,180 @@
+use super::test_utils::{
+    assert_balance, create_test_group, member, mint_tokens, setup_test_env, TestEnv,
+};
+use crate::base::errors::Error;
+use crate::base::types::{BatchItem, FeeMode, TokenAmount};
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Vec};
+
+/// Creates a group with `usages` usages that pays everything to `payee`.
+fn create_single_member_group(test_env: &TestEnv, payee: &Address, usages: u32) -> BytesN<32> {
+    let env = &test_env.env;
+    create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &test_env.users.get(0).unwrap(),
+        &vec![env, member(payee, 10_000)],
+        usages,
+        &test_env.mock_tokens.get(0).unwrap(),
+    )
+}
+
+fn item(id: &BytesN<32>, token: &Address, amount: i128) -> BatchItem {
+    BatchItem {
+        id: id.clone(),
+        token: token.clone(),
+        amount,
+    }
+}
+
+#[test]
+fn test_distribute_batch_splits_into_each_group() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let payee1 = Address::generate(env);
+    let payee2 = Address::generate(env);
+    let group1 = create_single_member_group(&test_env, &payee1, 1);
+    let group2 = create_single_member_group(&test_env, &payee2, 2);
+
+    mint_tokens(env, &token, &sender, 1000);
+    let summary = client.distribute_batch(
+        &sender,
+        &vec![
+            env,
+            item(&group1, &token, 300),
+            item(&group2, &token, 200),
+            item(&group2, &token, 100),
+        ],
+    );
+
+    assert_eq!(summary.distributions, 3);
+    assert_eq!(
+        summary.totals,
+        vec![
+            env,
+            TokenAmount {
+                token: token.clone(),
+                amount: 600
+            }
+        ]
+    );
+    assert_eq!(summary.fees, Vec::new(env));
+
+    assert_balance(env, &token, &sender, 400);
+    assert_balance(env, &token, &payee1, 300);
+    assert_balance(env, &token, &payee2, 300);
+    assert_eq!(client.get_remaining_usages(&group1), 0);
+    assert_eq!(client.get_remaining_usages(&group2), 0);
+    assert_eq!(client.get_group_distributions(&group1).len(), 1);
+    assert_eq!(client.get_group_distributions(&group2).len(), 2);
+    assert_eq!(
+        client
+            .get_group_distributions(&group2)
+            .get(1)
+            .unwrap()
+            .sender,
+        sender
+    );
+}
+
+#[test]
+fn test_distribute_batch_is_all_or_nothing() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let payee1 = Address::generate(env);
+    let group1 = create_single_member_group(&test_env, &payee1, 1);
+    let group2 = create_single_member_group(&test_env, &Address::generate(env), 2);
+
+    // The second distribution to the one-usage group fails the whole batch
+    mint_tokens(env, &token, &sender, 1000);
+    assert_eq!(
+        client.try_distribute_batch(
+            &sender,
+            &vec![
+                env,
+                item(&group1, &token, 300),
+                item(&group2, &token, 200),
+                item(&group1, &token, 100),
+            ],
+        ),
+        Err(Ok(Error::NoUsagesRemaining))
+    );
+
+    assert_balance(env, &token, &sender, 1000);
+    assert_balance(env, &token, &payee1, 0);
+    assert_eq!(client.get_remaining_usages(&group1), 1);
+    assert_eq!(client.get_group_distributions(&group2).len(), 0);
+}
+
+#[test]
+fn test_distribute_batch_validates_items() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let group = create_single_member_group(&test_env, &Address::generate(env), 1);
+    mint_tokens(env, &token, &sender, 1000);
+
+    assert_eq!(
+        client.try_distribute_batch(&sender, &Vec::new(env)),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_distribute_batch(&sender, &vec![env, item(&group, &token, 0)]),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_distribute_batch(
+            &sender,
+            &vec![env, item(&group, &Address::generate(env), 100)]
+        ),
+        Err(Ok(Error::UnsupportedToken))
+    );
+    assert_eq!(
+        client.try_distribute_batch(
+            &sender,
+            &vec![env, item(&BytesN::from_array(env, &[9u8; 32]), &token, 100)]
+        ),
+        Err(Ok(Error::NotFound))
+    );
+}
+
+#[test]
+fn test_distribute_batch_reports_protocol_fees() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let payee = Address::generate(env);
+    let group = create_single_member_group(&test_env, &payee, 1);
+
+    client.set_protocol_fee(&test_env.admin, &100);
+    client.set_fee_mode(&group, &creator, &FeeMode::Percentage);
+
+    mint_tokens(env, &token, &sender, 3000);
+    let summary = client.distribute_batch(
+        &sender,
+        &vec![env, item(&group, &token, 1000), item(&group, &token, 2000)],
+    );
+
+    assert_eq!(
+        summary.fees,
+        vec![
+            env,
+            TokenAmount {
+                token: token.clone(),
+                amount: 30
+            }
+        ]
+    );
+    assert_balance(env, &token, &payee, 2970);
+}
@@ -0,0 +1,178 @@
+use super::test_utils::{
+    assert_balance, create_test_group, deploy_mock_token, mint_tokens, setup_test_env,
+};
+use crate::base::errors::Error;
+use crate::base::types::{DistributionMode, GroupMember};
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, Address, String, Vec};
+
+fn two_member_split(
+    env: &soroban_sdk::Env,
+    member1: &Address,
+    member2: &Address,
+) -> Vec<GroupMember> {
+    let mut members = Vec::new(env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 7000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 3000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members
+}
+
+#[test]
+fn test_group_defaults_to_push_mode() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let members = two_member_split(&env, &Address::generate(&env), &Address::generate(&env));
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+
+    assert_eq!(client.get_distribution_mode(&id), DistributionMode::Push);
+}
+
+#[test]
+fn test_deleted_group_does_not_pass_on_claim_mode() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let members = two_member_split(&env, &Address::generate(&env), &Address::generate(&env));
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+    client.set_distribution_mode(&id, &creator, &DistributionMode::Claim);
+
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+
+    // A group re-created under the same id starts in push mode again
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+    assert_eq!(client.get_distribution_mode(&id), DistributionMode::Push);
+}
+
+#[test]
+fn test_claim_mode_credits_members_instead_of_transferring() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    let members = two_member_split(&env, &member1, &member2);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 2u32, &token);
+
+    client.set_distribution_mode(&id, &creator, &DistributionMode::Claim);
+    assert_eq!(client.get_distribution_mode(&id), DistributionMode::Claim);
+
+    let sender = test_env.users.get(1).unwrap().clone();
+    mint_tokens(&env, &token, &sender, 2000);
+    client.distribute(&id, &token, &1000, &sender);
+    client.distribute(&id, &token, &1000, &sender);
+
+    assert_balance(&env, &token, &member1, 0);
+    assert_balance(&env, &token, &member2, 0);
+    assert_eq!(client.get_claimable(&member1, &token), 1400);
+    assert_eq!(client.get_claimable(&member2, &token), 600);
+
+    // History still records what each member was credited
+    let history = client.get_group_distributions(&id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().member_amounts.len(), 2);
+
+    assert_eq!(client.claim(&member1, &token), 1400);
+    assert_balance(&env, &token, &member1, 1400);
+    assert_eq!(client.get_claimable(&member1, &token), 0);
+    assert_eq!(client.get_claimable(&member2, &token), 600);
+}
+
+#[test]
+fn test_claim_all_pays_every_token() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let token2 = deploy_mock_token(
+        &env,
+        &String::from_str(&env, "Second Token"),
+        &String::from_str(&env, "TWO"),
+    );
+    client.add_supported_token(&token2, &test_env.admin);
+
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    let members = two_member_split(&env, &member1, &member2);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 2u32, &token);
+    client.set_distribution_mode(&id, &creator, &DistributionMode::Claim);
+
+    let sender = test_env.users.get(1).unwrap().clone();
+    mint_tokens(&env, &token, &sender, 1000);
+    mint_tokens(&env, &token2, &sender, 500);
+    client.distribute(&id, &token, &1000, &sender);
+    client.distribute(&id, &token2, &500, &sender);
+
+    let claimed = client.claim_all(&member2);
+    assert_eq!(claimed.len(), 2);
+    assert_eq!(claimed.get(0).unwrap().token, token);
+    assert_eq!(claimed.get(0).unwrap().amount, 300);
+    assert_eq!(claimed.get(1).unwrap().token, token2);
+    assert_eq!(claimed.get(1).unwrap().amount, 150);
+
+    assert_balance(&env, &token, &member2, 300);
+    assert_balance(&env, &token2, &member2, 150);
+    assert_eq!(client.get_claimable(&member2, &token), 0);
+    assert_eq!(client.get_claimable(&member2, &token2), 0);
+}
+
+#[test]
+fn test_claim_without_balance_fails() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+
+    let member = Address::generate(&env);
+    assert_eq!(
+        client.try_claim(&member, &token),
+        Err(Ok(Error::NothingToClaim))
+    );
+}
+
+#[test]
+fn test_non_creator_cannot_set_distribution_mode() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let members = two_member_split(&env, &Address::generate(&env), &Address::generate(&env));
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+
+    let other = test_env.users.get(1).unwrap().clone();
+    assert_eq!(
+        client.try_set_distribution_mode(&id, &other, &DistributionMode::Claim),
+        Err(Ok(Error::Unauthorized))
+    );
+}
@@ -0,0 +1,325 @@
+use super::test_utils::{accrue_fee_revenue, setup_test_env, TestEnv};
+use crate::base::errors::Error;
+use crate::base::types::{AdminAction, Role};
+use crate::mock_token::MockTokenClient;
+use crate::AutoShareContractClient;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Vec,
+};
+
+/// Enables a 2-of-3 council and returns its signers.
+fn enable_council(test_env: &TestEnv, client: &AutoShareContractClient) -> Vec<Address> {
+    let mut signers = Vec::new(&test_env.env);
+    for _ in 0..3 {
+        signers.push_back(Address::generate(&test_env.env));
+    }
+    client.set_council(&test_env.admin, &signers, &2);
+    signers
+}
+
+#[test]
+fn test_set_council() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    assert_eq!(client.get_council(), None);
+    let signers = enable_council(&test_env, &client);
+
+    let council = client.get_council().unwrap();
+    assert_eq!(council.signers, signers);
+    assert_eq!(council.threshold, 2);
+}
+
+#[test]
+fn test_set_council_validates_signers_and_threshold() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+
+    let signer = Address::generate(env);
+    let mut signers = Vec::new(env);
+    signers.push_back(signer.clone());
+    signers.push_back(Address::generate(env));
+
+    assert_eq!(
+        client.try_set_council(&test_env.admin, &signers, &0),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_council(&test_env.admin, &signers, &3),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_council(&test_env.admin, &Vec::new(env), &1),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    signers.push_back(signer);
+    assert_eq!(
+        client.try_set_council(&test_env.admin, &signers, &2),
+        Err(Ok(Error::DuplicateMember))
+    );
+}
+
+#[test]
+fn test_set_council_requires_admin() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+
+    let mut signers = Vec::new(env);
+    signers.push_back(Address::generate(env));
+    let not_admin = Address::generate(env);
+
+    assert_eq!(
+        client.try_set_council(&not_admin, &signers, &1),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_admin_entrypoints_blocked_in_council_mode() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let admin = &test_env.admin;
+
+    let signers = enable_council(&test_env, &client);
+
+    assert_eq!(
+        client.try_withdraw(admin, &token, &1, admin),
+        Err(Ok(Error::CouncilModeActive))
+    );
+    assert_eq!(client.try_pause(admin), Err(Ok(Error::CouncilModeActive)));
+    assert_eq!(
+        client.try_set_usage_fee(&token, &20, admin),
+        Err(Ok(Error::CouncilModeActive))
+    );
+    assert_eq!(
+        client.try_propose_admin(admin, &Address::generate(env), &None),
+        Err(Ok(Error::CouncilModeActive))
+    );
+    assert_eq!(
+        client.try_set_council(admin, &signers, &1),
+        Err(Ok(Error::CouncilModeActive))
+    );
+
+    // Data migrations stay with the admin key
+    client.reindex_groups(admin, &0, &10);
+}
+
+#[test]
+fn test_withdraw_executes_at_threshold() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let recipient = Address::generate(env);
+
+    accrue_fee_revenue(env, contract, &token, 100);
+    let signers = enable_council(&test_env, &client);
+
+    let action = AdminAction::Withdraw(token.clone(), 400, recipient.clone());
+    let proposal_id = client.propose_action(&signers.get(0).unwrap(), &action);
+
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.action, action);
+    assert_eq!(proposal.approvals.len(), 1);
+    assert!(!proposal.executed);
+    assert_eq!(client.get_contract_balance(&token), 1000);
+
+    assert!(client.approve_action(&signers.get(1).unwrap(), &proposal_id));
+
+    assert!(client.get_proposal(&proposal_id).executed);
+    assert_eq!(client.get_contract_balance(&token), 600);
+    assert_eq!(MockTokenClient::new(env, &token).balance(&recipient), 400);
+
+    assert_eq!(
+        client.try_approve_action(&signers.get(2).unwrap(), &proposal_id),
+        Err(Ok(Error::ProposalAlreadyExecuted))
+    );
+}
+
+#[test]
+fn test_threshold_of_one_executes_on_proposal() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    let signer = Address::generate(env);
+    let mut signers = Vec::new(env);
+    signers.push_back(signer.clone());
+    client.set_council(&test_env.admin, &signers, &1);
+
+    let proposal_id = client.propose_action(&signer, &AdminAction::SetUsageFee(token.clone(), 42));
+
+    assert!(client.get_proposal(&proposal_id).executed);
+    assert_eq!(client.get_usage_fee(&token), 42);
+}
+
+#[test]
+fn test_only_signers_can_propose_and_approve() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let outsider = Address::generate(env);
+
+    let signers = enable_council(&test_env, &client);
+
+    assert_eq!(
+        client.try_propose_action(&outsider, &AdminAction::Pause),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    let proposal_id = client.propose_action(&signers.get(0).unwrap(), &AdminAction::Pause);
+    assert_eq!(
+        client.try_approve_action(&outsider, &proposal_id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_approve_action(&signers.get(0).unwrap(), &proposal_id),
+        Err(Ok(Error::AlreadyExists))
+    );
+    assert_eq!(
+        client.try_approve_action(&signers.get(1).unwrap(), &99),
+        Err(Ok(Error::NotFound))
+    );
+    assert!(!client.get_paused_status());
+}
+
+#[test]
+fn test_propose_without_council_fails() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+
+    assert_eq!(
+        client.try_propose_action(&test_env.admin, &AdminAction::Pause),
+        Err(Ok(Error::CouncilNotEnabled))
+    );
+}
+
+#[test]
+fn test_failed_action_leaves_proposal_pending() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+
+    let signers = enable_council(&test_env, &client);
+    let action = AdminAction::Withdraw(token.clone(), 500, Address::generate(env));
+    let proposal_id = client.propose_action(&signers.get(0).unwrap(), &action);
+
+    assert_eq!(
+        client.try_approve_action(&signers.get(1).unwrap(), &proposal_id),
+        Err(Ok(Error::InsufficientFeeRevenue))
+    );
+    let proposal = client.get_proposal(&proposal_id);
+    assert!(!proposal.executed);
+    assert_eq!(proposal.approvals.len(), 1);
+
+    // Once enough fees have been collected, the approval goes through
+    accrue_fee_revenue(env, &test_env.autoshare_contract, &token, 50);
+    assert!(client.approve_action(&signers.get(1).unwrap(), &proposal_id));
+}
+
+#[test]
+fn test_council_grants_roles() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let pauser = Address::generate(env);
+
+    let signers = enable_council(&test_env, &client);
+    let proposal_id = client.propose_action(
+        &signers.get(0).unwrap(),
+        &AdminAction::GrantRole(Role::Pauser, pauser.clone()),
+    );
+    client.approve_action(&signers.get(2).unwrap(), &proposal_id);
+
+    assert!(client.has_role(&Role::Pauser, &pauser));
+
+    // The role takes effect once the council is disabled
+    assert_eq!(client.try_pause(&pauser), Err(Ok(Error::CouncilModeActive)));
+    let proposal_id = client.propose_action(&signers.get(0).unwrap(), &AdminAction::DisableCouncil);
+    client.approve_action(&signers.get(1).unwrap(), &proposal_id);
+    client.pause(&pauser);
+    assert!(client.get_paused_status());
+}
+
+#[test]
+fn test_roles_blocked_in_council_mode() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let treasurer = Address::generate(env);
+    let recipient = Address::generate(env);
+
+    accrue_fee_revenue(env, contract, &token, 100);
+    client.grant_role(&test_env.admin, &Role::Treasurer, &treasurer);
+    client.set_timelock_delay(&test_env.admin, &3600);
+    let action_id = client.queue_action(
+        &treasurer,
+        &AdminAction::Withdraw(token.clone(), 50, recipient.clone()),
+    );
+    enable_council(&test_env, &client);
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+
+    assert_eq!(
+        client.try_withdraw(&treasurer, &token, &50, &recipient),
+        Err(Ok(Error::CouncilModeActive))
+    );
+    assert_eq!(
+        client.try_queue_action(
+            &treasurer,
+            &AdminAction::Withdraw(token.clone(), 50, recipient.clone())
+        ),
+        Err(Ok(Error::CouncilModeActive))
+    );
+    // Actions queued before the council was set do not run either
+    assert_eq!(
+        client.try_execute_queued_action(&action_id),
+        Err(Ok(Error::CouncilModeActive))
+    );
+    assert_eq!(MockTokenClient::new(env, &token).balance(&recipient), 0);
+}
+
+#[test]
+fn test_disable_council_restores_admin() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    let signers = enable_council(&test_env, &client);
+    let proposal_id = client.propose_action(&signers.get(0).unwrap(), &AdminAction::DisableCouncil);
+    client.approve_action(&signers.get(1).unwrap(), &proposal_id);
+
+    assert_eq!(client.get_council(), None);
+    client.set_usage_fee(&token, &20, &test_env.admin);
+    assert_eq!(client.get_usage_fee(&token), 20);
+}
+
+#[test]
+fn test_council_can_change_admin() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let new_admin = Address::generate(env);
+
+    let signers = enable_council(&test_env, &client);
+    let proposal_id = client.propose_action(
+        &signers.get(0).unwrap(),
+        &AdminAction::ProposeAdmin(new_admin.clone(), None),
+    );
+    client.approve_action(&signers.get(1).unwrap(), &proposal_id);
+
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+}
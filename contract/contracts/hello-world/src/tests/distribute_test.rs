@@ -1,4 +1,5 @@
 use super::test_utils::{assert_balance, create_test_group, mint_tokens, setup_test_env};
+use crate::base::errors::Error;
 use crate::base::types::GroupMember;
 use crate::mock_token::MockTokenClient;
 use crate::AutoShareContractClient;
@@ -19,15 +20,24 @@ fn test_distribute_splits_payment_and_decrements_usage() {
     let mut members = Vec::new(&env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 50,
+        percentage: 5000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 30,
+        percentage: 3000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member3.clone(),
-        percentage: 20,
+        percentage: 2000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     let creator = test_env.users.get(0).unwrap().clone();
@@ -65,6 +75,7 @@ fn test_distribute_splits_payment_and_decrements_usage() {
     assert_eq!(dist.sender, sender);
     assert_eq!(dist.total_amount, amount);
     assert_eq!(dist.token, token);
+    assert!(!dist.is_deposit);
     assert_eq!(dist.distribution_number, 0); // 1st distribution: total_usages_paid - usage_count = 2 - 2
     assert_eq!(dist.member_amounts.len(), 3);
 
@@ -84,7 +95,6 @@ fn test_distribute_splits_payment_and_decrements_usage() {
 }
 
 #[test]
-#[should_panic]
 fn test_distribute_fails_when_group_inactive() {
     let test_env = setup_test_env();
     let env = test_env.env;
@@ -98,11 +108,17 @@ fn test_distribute_fails_when_group_inactive() {
     let mut members = Vec::new(&env);
     members.push_back(GroupMember {
         address: member1.clone(),
-        percentage: 60,
+        percentage: 6000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
     members.push_back(GroupMember {
         address: member2.clone(),
-        percentage: 40,
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     let creator = test_env.users.get(0).unwrap().clone();
@@ -112,5 +128,318 @@ fn test_distribute_fails_when_group_inactive() {
 
     let sender = test_env.users.get(1).unwrap().clone();
     mint_tokens(&env, &token, &sender, 500);
-    client.distribute(&id, &token, &500, &sender);
+    assert_eq!(
+        client.try_distribute(&id, &token, &500, &sender),
+        Err(Ok(Error::GroupInactive))
+    );
+}
+
+#[test]
+fn test_distribute_basis_point_splits() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    let member3 = Address::generate(&env);
+
+    // 33.33% / 33.33% / 33.34%
+    let mut members = Vec::new(&env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 3333,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 3333,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member3.clone(),
+        percentage: 3334,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+
+    let sender = test_env.users.get(1).unwrap().clone();
+    mint_tokens(&env, &token, &sender, 10_001);
+    client.distribute(&id, &token, &10_001, &sender);
+
+    assert_balance(&env, &token, &member1, 3333);
+    assert_balance(&env, &token, &member2, 3333);
+    // Last member receives the remainder
+    assert_balance(&env, &token, &member3, 3335);
+}
+
+#[test]
+fn test_preview_distribution_matches_distribute() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    let member3 = Address::generate(&env);
+
+    let mut members = Vec::new(&env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 3333,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 3333,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member3.clone(),
+        percentage: 3334,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+
+    let preview = client.preview_distribution(&id, &token, &1000);
+    assert_eq!(preview.len(), 3);
+    assert_eq!(preview.get(0).unwrap().address, member1);
+    assert_eq!(preview.get(0).unwrap().amount, 333);
+    assert_eq!(preview.get(1).unwrap().amount, 333);
+    // Last member receives the remainder
+    assert_eq!(preview.get(2).unwrap().amount, 334);
+
+    // Previewing does not consume a usage
+    assert_eq!(client.get_remaining_usages(&id), 1);
+
+    let sender = test_env.users.get(1).unwrap().clone();
+    mint_tokens(&env, &token, &sender, 1000);
+    client.distribute(&id, &token, &1000, &sender);
+
+    let recorded = client.get_group_distributions(&id).get(0).unwrap();
+    assert_eq!(recorded.member_amounts, preview);
+}
+
+#[test]
+fn test_preview_distribution_fails_when_group_inactive() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let mut members = Vec::new(&env);
+    members.push_back(GroupMember {
+        address: Address::generate(&env),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+    client.deactivate_group(&id, &creator);
+
+    assert_eq!(
+        client.try_preview_distribution(&id, &token, &1000),
+        Err(Ok(Error::GroupInactive))
+    );
+}
+
+#[test]
+fn test_preview_distribution_rejects_zero_amount() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let mut members = Vec::new(&env);
+    members.push_back(GroupMember {
+        address: Address::generate(&env),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1u32, &token);
+
+    assert_eq!(
+        client.try_preview_distribution(&id, &token, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_fixed_members_are_paid_before_percentage_split() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let agency = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+
+    // The fixed member sits between the percentage members on purpose
+    let mut members = Vec::new(&env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 6000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: agency.clone(),
+        percentage: 0,
+        fixed_amount: 50,
+        fixed_token: Some(token.clone()),
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 4000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 2, &token);
+
+    let sender = test_env.users.get(1).unwrap().clone();
+    mint_tokens(&env, &token, &sender, 1051);
+    client.distribute(&id, &token, &1051, &sender);
+
+    assert_balance(&env, &token, &agency, 50);
+    assert_balance(&env, &token, &member1, 600);
+    assert_balance(&env, &token, &member2, 401);
+
+    // Exactly the fixed portion leaves nothing for the percentage members
+    let preview = client.preview_distribution(&id, &token, &50);
+    assert_eq!(preview.len(), 1);
+    assert_eq!(preview.get(0).unwrap().address, agency);
+}
+
+#[test]
+fn test_distribute_fails_when_amount_below_fixed_shares() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+
+    let mut members = Vec::new(&env);
+    members.push_back(GroupMember {
+        address: Address::generate(&env),
+        percentage: 0,
+        fixed_amount: 500,
+        fixed_token: Some(token.clone()),
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: Address::generate(&env),
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &members, 1, &token);
+
+    let sender = test_env.users.get(1).unwrap().clone();
+    mint_tokens(&env, &token, &sender, 499);
+    assert_eq!(
+        client.try_distribute(&id, &token, &499, &sender),
+        Err(Ok(Error::AmountBelowFixedShares))
+    );
+    assert_eq!(client.get_remaining_usages(&id), 1);
+}
+
+#[test]
+fn test_validate_mixed_members() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_test_group(&env, &contract, &creator, &Vec::new(&env), 1, &token);
+
+    let member = |percentage: u32, fixed_amount: i128| GroupMember {
+        address: Address::generate(&env),
+        percentage,
+        fixed_amount,
+        fixed_token: (fixed_amount != 0).then(|| token.clone()),
+        group: None,
+    };
+
+    // A member cannot take both a fixed amount and a percentage
+    let mut both = Vec::new(&env);
+    both.push_back(member(5000, 10));
+    both.push_back(member(5000, 0));
+    assert_eq!(
+        client.try_update_members(&id, &creator, &both),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    let mut negative = Vec::new(&env);
+    negative.push_back(member(0, -1));
+    negative.push_back(member(10000, 0));
+    assert_eq!(
+        client.try_update_members(&id, &creator, &negative),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    // A fixed amount needs the token it is paid in
+    let mut no_token = Vec::new(&env);
+    no_token.push_back(GroupMember {
+        fixed_token: None,
+        ..member(0, 100)
+    });
+    no_token.push_back(member(10000, 0));
+    assert_eq!(
+        client.try_update_members(&id, &creator, &no_token),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    // Someone must take the remainder
+    let mut fixed_only = Vec::new(&env);
+    fixed_only.push_back(member(0, 100));
+    assert_eq!(
+        client.try_update_members(&id, &creator, &fixed_only),
+        Err(Ok(Error::InvalidTotalPercentage))
+    );
+
+    let mut mixed = Vec::new(&env);
+    mixed.push_back(member(0, 100));
+    mixed.push_back(member(10000, 0));
+    client.update_members(&id, &creator, &mixed);
+    assert_eq!(client.get(&id).members, mixed);
 }
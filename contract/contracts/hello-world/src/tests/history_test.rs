@@ -0,0 +1,287 @@
+use super::test_utils::{create_test_group, create_test_members, mint_tokens, setup_test_env};
+use crate::autoshare_logic::DataKey;
+use crate::base::errors::Error;
+use crate::base::types::{MemberAmount, PaymentHistory};
+use crate::AutoShareContractClient;
+use soroban_sdk::{contracttype, testutils::Address as _, vec, Address, BytesN, Env, Vec};
+
+/// `PaymentHistory` as earlier versions stored it.
+#[contracttype]
+#[derive(Clone)]
+struct LegacyPaymentHistory {
+    user: Address,
+    group_id: BytesN<32>,
+    usages_purchased: u32,
+    amount_paid: i128,
+    timestamp: u64,
+}
+
+/// `DistributionHistory` as earlier versions stored it.
+#[contracttype]
+#[derive(Clone)]
+struct LegacyDistributionHistory {
+    group_id: BytesN<32>,
+    sender: Address,
+    total_amount: i128,
+    token: Address,
+    member_amounts: Vec<MemberAmount>,
+    timestamp: u64,
+    distribution_number: u32,
+}
+
+fn legacy_payment(user: &Address, group_id: &BytesN<32>, usages: u32) -> LegacyPaymentHistory {
+    LegacyPaymentHistory {
+        user: user.clone(),
+        group_id: group_id.clone(),
+        usages_purchased: usages,
+        amount_paid: (usages as i128) * 10,
+        timestamp: 0,
+    }
+}
+
+/// Stores two payments the way earlier versions did, as one vector per user
+/// and group, and returns them as they read back.
+fn store_legacy_payments(
+    env: &Env,
+    contract: &Address,
+    user: &Address,
+    group_id: &BytesN<32>,
+) -> Vec<PaymentHistory> {
+    let legacy = vec![
+        env,
+        legacy_payment(user, group_id, 7),
+        legacy_payment(user, group_id, 8),
+    ];
+    env.as_contract(contract, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::UserPaymentHistory(user.clone()), &legacy);
+        env.storage()
+            .persistent()
+            .set(&DataKey::GroupPaymentHistory(group_id.clone()), &legacy);
+    });
+
+    let mut payments = Vec::new(env);
+    for entry in legacy.iter() {
+        payments.push_back(PaymentHistory {
+            user: entry.user,
+            group_id: entry.group_id,
+            usages_purchased: entry.usages_purchased,
+            amount_paid: entry.amount_paid,
+            timestamp: entry.timestamp,
+            discount_bps: 0,
+        });
+    }
+    payments
+}
+
+#[test]
+fn test_history_is_stored_as_numbered_entries() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let sender = test_env.users.get(1).unwrap().clone();
+    let members = create_test_members(&env, 2);
+    let member = members.get(0).unwrap().address;
+
+    let id = create_test_group(&env, &contract, &creator, &members, 2, &token);
+    mint_tokens(&env, &token, &sender, 300);
+    client.distribute(&id, &token, &100, &sender);
+    client.distribute(&id, &token, &200, &sender);
+
+    env.as_contract(&contract, || {
+        let storage = env.storage().persistent();
+        assert_eq!(
+            storage.get::<_, u32>(&DataKey::GroupDistributionCount(id.clone())),
+            Some(2)
+        );
+        assert_eq!(
+            storage.get::<_, u32>(&DataKey::MemberDistributionCount(member.clone())),
+            Some(2)
+        );
+        assert_eq!(
+            storage.get::<_, u32>(&DataKey::GroupPaymentCount(id.clone())),
+            Some(1)
+        );
+        assert!(!storage.has(&DataKey::GroupDistributionHistory(id.clone())));
+        assert!(!storage.has(&DataKey::GroupPaymentHistory(id.clone())));
+    });
+
+    let history = client.get_group_distributions(&id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().total_amount, 100);
+    assert_eq!(history.get(1).unwrap().total_amount, 200);
+}
+
+#[test]
+fn test_legacy_history_is_readable_before_migration() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(&env, &contract);
+    let user = Address::generate(&env);
+    let group_id = BytesN::from_array(&env, &[7u8; 32]);
+
+    let payments = store_legacy_payments(&env, &contract, &user, &group_id);
+
+    assert_eq!(client.get_user_payment_history(&user), payments);
+    let page = client.get_group_payment_history_page(&group_id, &1, &5);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().usages_purchased, 8);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_migrate_history_moves_legacy_vectors() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(&env, &contract);
+    let user = Address::generate(&env);
+    let group_id = BytesN::from_array(&env, &[7u8; 32]);
+
+    let payments = store_legacy_payments(&env, &contract, &user, &group_id);
+
+    let mut group_ids = Vec::new(&env);
+    group_ids.push_back(group_id.clone());
+    let mut accounts = Vec::new(&env);
+    accounts.push_back(user.clone());
+
+    assert_eq!(
+        client.migrate_history(&test_env.admin, &group_ids, &accounts),
+        4
+    );
+
+    env.as_contract(&contract, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&DataKey::UserPaymentHistory(user.clone())));
+        assert!(!storage.has(&DataKey::GroupPaymentHistory(group_id.clone())));
+        assert_eq!(
+            storage.get::<_, u32>(&DataKey::UserPaymentCount(user.clone())),
+            Some(2)
+        );
+    });
+    assert_eq!(client.get_user_payment_history(&user), payments);
+    assert_eq!(client.get_group_payment_history(&group_id), payments);
+
+    // Running it again moves nothing
+    assert_eq!(
+        client.migrate_history(&test_env.admin, &group_ids, &accounts),
+        0
+    );
+    assert_eq!(client.get_user_payment_history(&user), payments);
+}
+
+#[test]
+fn test_legacy_distributions_are_upgraded() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(&env, &contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let sender = Address::generate(&env);
+    let member = Address::generate(&env);
+    let group_id = BytesN::from_array(&env, &[7u8; 32]);
+
+    let legacy = vec![
+        &env,
+        LegacyDistributionHistory {
+            group_id: group_id.clone(),
+            sender: sender.clone(),
+            total_amount: 500,
+            token: token.clone(),
+            member_amounts: vec![
+                &env,
+                MemberAmount {
+                    address: member.clone(),
+                    amount: 500,
+                },
+            ],
+            timestamp: 0,
+            distribution_number: 1,
+        },
+    ];
+    env.as_contract(&contract, || {
+        let storage = env.storage().persistent();
+        storage.set(
+            &DataKey::GroupDistributionHistory(group_id.clone()),
+            &legacy,
+        );
+        storage.set(&DataKey::MemberDistributionHistory(member.clone()), &legacy);
+    });
+
+    let history = client.get_group_distributions(&group_id);
+    assert_eq!(history.len(), 1);
+    let entry = history.get(0).unwrap();
+    assert_eq!(entry.sender, sender);
+    assert_eq!(entry.total_amount, 500);
+    assert_eq!(entry.member_amounts.get(0).unwrap().address, member);
+    assert!(!entry.is_deposit);
+    assert_eq!(entry.fee_amount, 0);
+    assert_eq!(entry.tranches.len(), 0);
+    assert_eq!(entry.parent_group, None);
+    assert_eq!(entry.other_tokens.len(), 0);
+
+    assert_eq!(
+        client.migrate_history(
+            &test_env.admin,
+            &vec![&env, group_id.clone()],
+            &vec![&env, member.clone()]
+        ),
+        2
+    );
+    assert_eq!(client.get_group_distributions(&group_id), history);
+    assert_eq!(client.get_member_distributions(&member), history);
+}
+
+#[test]
+fn test_new_entry_migrates_legacy_history_first() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    let id = create_test_group(&env, &contract, &creator, &members, 1, &token);
+    let legacy_group = BytesN::from_array(&env, &[7u8; 32]);
+
+    // The creator's history is rewritten in the legacy layout
+    env.as_contract(&contract, || {
+        let storage = env.storage().persistent();
+        storage.remove(&DataKey::UserPaymentCount(creator.clone()));
+        storage.remove(&DataKey::UserPayment(creator.clone(), 0));
+    });
+    store_legacy_payments(&env, &contract, &creator, &legacy_group);
+
+    client.topup_subscription(&id, &3, &token, &creator, &None);
+
+    let history = client.get_user_payment_history(&creator);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap().usages_purchased, 7);
+    assert_eq!(history.get(1).unwrap().usages_purchased, 8);
+    assert_eq!(history.get(2).unwrap().usages_purchased, 3);
+    env.as_contract(&contract, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DataKey::UserPaymentHistory(creator.clone())));
+    });
+}
+
+#[test]
+fn test_migrate_history_requires_admin() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let not_admin = Address::generate(&env);
+
+    assert_eq!(
+        client.try_migrate_history(&not_admin, &Vec::new(&env), &Vec::new(&env)),
+        Err(Ok(Error::Unauthorized))
+    );
+}
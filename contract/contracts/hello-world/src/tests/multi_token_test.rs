@@ -0,0 +1,220 @@
+use super::test_utils::{
+    assert_balance, create_test_group, deploy_mock_token, member, mint_tokens, setup_test_env,
+    TestEnv,
+};
+use crate::base::errors::Error;
+use crate::base::types::{GroupMember, TokenAmount};
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, String, Vec};
+
+fn token_amount(token: &Address, amount: i128) -> TokenAmount {
+    TokenAmount {
+        token: token.clone(),
+        amount,
+    }
+}
+
+/// Deploys and supports a second token and funds `sender` with both tokens.
+fn setup_second_token(
+    test_env: &TestEnv,
+    client: &AutoShareContractClient,
+    sender: &Address,
+) -> Address {
+    let env = &test_env.env;
+    let token2 = deploy_mock_token(
+        env,
+        &String::from_str(env, "Second Token"),
+        &String::from_str(env, "TWO"),
+    );
+    client.add_supported_token(&token2, &test_env.admin);
+    mint_tokens(env, &test_env.mock_tokens.get(0).unwrap(), sender, 1000);
+    mint_tokens(env, &token2, sender, 1000);
+    token2
+}
+
+#[test]
+fn test_distribute_multi_splits_each_token_for_one_usage() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let token2 = setup_second_token(&test_env, &client, &sender);
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &test_env.users.get(0).unwrap(),
+        &vec![env, member(&member1, 2500), member(&member2, 7500)],
+        2,
+        &token,
+    );
+
+    client.distribute_multi(
+        &id,
+        &vec![env, token_amount(&token, 1000), token_amount(&token2, 400)],
+        &sender,
+    );
+
+    assert_balance(env, &token, &member1, 250);
+    assert_balance(env, &token, &member2, 750);
+    assert_balance(env, &token2, &member1, 100);
+    assert_balance(env, &token2, &member2, 300);
+    assert_eq!(client.get_remaining_usages(&id), 1);
+
+    let history = client.get_group_distributions(&id);
+    assert_eq!(history.len(), 1);
+    let dist = history.get(0).unwrap();
+    assert_eq!(dist.token, token);
+    assert_eq!(dist.total_amount, 1000);
+    assert_eq!(dist.other_tokens.len(), 1);
+    let other = dist.other_tokens.get(0).unwrap();
+    assert_eq!(other.token, token2);
+    assert_eq!(other.total_amount, 400);
+    assert_eq!(other.member_amounts.get(1).unwrap().amount, 300);
+    assert_eq!(client.get_member_distributions(&member1).len(), 1);
+}
+
+#[test]
+fn test_distribute_multi_validates_amounts() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let token2 = setup_second_token(&test_env, &client, &sender);
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &test_env.users.get(0).unwrap(),
+        &vec![env, member(&Address::generate(env), 10_000)],
+        1,
+        &token,
+    );
+
+    assert_eq!(
+        client.try_distribute_multi(&id, &Vec::new(env), &sender),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_distribute_multi(
+            &id,
+            &vec![env, token_amount(&token, 100), token_amount(&token, 100)],
+            &sender
+        ),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_distribute_multi(
+            &id,
+            &vec![env, token_amount(&token, 100), token_amount(&token2, 0)],
+            &sender
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_distribute_multi(
+            &id,
+            &vec![
+                env,
+                token_amount(&token, 100),
+                token_amount(&Address::generate(env), 100)
+            ],
+            &sender
+        ),
+        Err(Ok(Error::UnsupportedToken))
+    );
+    assert_balance(env, &token, &sender, 1000);
+    assert_eq!(client.get_remaining_usages(&id), 1);
+}
+
+#[test]
+fn test_distribute_multi_passes_all_tokens_to_child_group_at_once() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let token2 = setup_second_token(&test_env, &client, &sender);
+
+    let team_member = Address::generate(env);
+    let team = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &vec![env, member(&team_member, 10_000)],
+        2,
+        &token,
+    );
+    let lead = GroupMember {
+        group: Some(team.clone()),
+        ..member(&Address::generate(env), 7500)
+    };
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &vec![env, member(&Address::generate(env), 2500), lead],
+        1,
+        &token,
+    );
+
+    client.distribute_multi(
+        &id,
+        &vec![env, token_amount(&token, 1000), token_amount(&token2, 400)],
+        &sender,
+    );
+
+    assert_balance(env, &token, &team_member, 750);
+    assert_balance(env, &token2, &team_member, 300);
+    assert_eq!(client.get_remaining_usages(&team), 1);
+    let child_dist = client.get_group_distributions(&team).get(0).unwrap();
+    assert_eq!(child_dist.parent_group, Some(id));
+    assert_eq!(child_dist.other_tokens.len(), 1);
+}
+
+#[test]
+fn test_distribute_multi_pays_fixed_amounts_from_their_token() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let token2 = setup_second_token(&test_env, &client, &sender);
+    let fixed = Address::generate(env);
+    let member1 = Address::generate(env);
+    let fixed_member = GroupMember {
+        fixed_amount: 60,
+        fixed_token: Some(token2.clone()),
+        ..member(&fixed, 0)
+    };
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &test_env.users.get(0).unwrap(),
+        &vec![env, fixed_member, member(&member1, 10_000)],
+        2,
+        &token,
+    );
+
+    // The fixed amount is paid from its own token, even when it is listed second
+    client.distribute_multi(
+        &id,
+        &vec![env, token_amount(&token, 500), token_amount(&token2, 100)],
+        &sender,
+    );
+
+    assert_balance(env, &token, &fixed, 0);
+    assert_balance(env, &token, &member1, 500);
+    assert_balance(env, &token2, &fixed, 60);
+    assert_balance(env, &token2, &member1, 40);
+
+    // A distribution without the fixed amount's token cannot pay it
+    assert_eq!(
+        client.try_distribute(&id, &token, &500, &sender),
+        Err(Ok(Error::FixedAmountTokenMismatch))
+    );
+    assert_eq!(client.get_remaining_usages(&id), 1);
+}
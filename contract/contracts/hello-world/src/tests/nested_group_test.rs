@@ -0,0 +1,289 @@
+use super::test_utils::{assert_balance, create_test_group, member, mint_tokens, setup_test_env};
+use crate::base::errors::Error;
+use crate::base::types::GroupMember;
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Vec};
+
+fn child_member(address: &Address, percentage: u32, group: &BytesN<32>) -> GroupMember {
+    GroupMember {
+        group: Some(group.clone()),
+        ..member(address, percentage)
+    }
+}
+
+#[test]
+fn test_distribute_splits_child_share_through_child_group() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let (alice, lead, bob, carol) = (
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    );
+
+    let team = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&bob, 6000), member(&carol, 4000)],
+        2,
+        &token,
+    );
+    let collective = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&alice, 5000), child_member(&lead, 5000, &team)],
+        3,
+        &token,
+    );
+
+    mint_tokens(env, &token, &sender, 1000);
+    client.distribute(&collective, &token, &1000, &sender);
+
+    assert_balance(env, &token, &alice, 500);
+    assert_balance(env, &token, &bob, 300);
+    assert_balance(env, &token, &carol, 200);
+    assert_balance(env, &token, &lead, 0);
+
+    // Each level spends a usage and records its own history entry
+    assert_eq!(client.get_remaining_usages(&collective), 2);
+    assert_eq!(client.get_remaining_usages(&team), 1);
+
+    let parent_dist = client.get_group_distributions(&collective).get(0).unwrap();
+    assert_eq!(parent_dist.parent_group, None);
+    assert_eq!(parent_dist.member_amounts.get(1).unwrap().amount, 500);
+
+    let child_dist = client.get_group_distributions(&team).get(0).unwrap();
+    assert_eq!(child_dist.parent_group, Some(collective));
+    assert_eq!(child_dist.sender, sender);
+    assert_eq!(child_dist.total_amount, 500);
+    assert_eq!(client.get_member_distributions(&bob).len(), 1);
+}
+
+#[test]
+fn test_child_without_usages_blocks_parent_distribution() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let alice = Address::generate(env);
+
+    let team = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&Address::generate(env), 10_000)],
+        1,
+        &token,
+    );
+    let collective = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![
+            env,
+            member(&alice, 5000),
+            child_member(&Address::generate(env), 5000, &team),
+        ],
+        2,
+        &token,
+    );
+
+    mint_tokens(env, &token, &sender, 2000);
+    client.distribute(&collective, &token, &1000, &sender);
+    assert_eq!(
+        client.try_distribute(&collective, &token, &1000, &sender),
+        Err(Ok(Error::NoUsagesRemaining))
+    );
+    assert_balance(env, &token, &alice, 500);
+    assert_eq!(client.get_remaining_usages(&collective), 1);
+}
+
+#[test]
+fn test_update_members_rejects_cycles() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let lead = Address::generate(env);
+
+    let team = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&Address::generate(env), 10_000)],
+        1,
+        &token,
+    );
+    let collective = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, child_member(&lead, 10_000, &team)],
+        2,
+        &token,
+    );
+
+    assert_eq!(
+        client.try_update_members(
+            &team,
+            &creator,
+            &vec![env, child_member(&lead, 10_000, &collective)]
+        ),
+        Err(Ok(Error::NestedGroupCycle))
+    );
+    assert_eq!(
+        client.try_update_members(
+            &team,
+            &creator,
+            &vec![env, child_member(&lead, 10_000, &team)]
+        ),
+        Err(Ok(Error::NestedGroupCycle))
+    );
+    assert_eq!(
+        client.try_update_members(
+            &team,
+            &creator,
+            &vec![
+                env,
+                child_member(&lead, 10_000, &BytesN::from_array(env, &[9u8; 32]))
+            ]
+        ),
+        Err(Ok(Error::NotFound))
+    );
+}
+
+#[test]
+fn test_update_members_limits_nesting_depth() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let lead = Address::generate(env);
+
+    // Each group nests the previous one, three levels below the last
+    let mut group = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&Address::generate(env), 10_000)],
+        1,
+        &token,
+    );
+    for usages in 2..=4 {
+        group = create_test_group(
+            env,
+            contract,
+            &creator,
+            &vec![env, child_member(&lead, 10_000, &group)],
+            usages,
+            &token,
+        );
+    }
+
+    let top = create_test_group(env, contract, &creator, &Vec::new(env), 5, &token);
+    assert_eq!(
+        client.try_update_members(
+            &top,
+            &creator,
+            &vec![env, child_member(&lead, 10_000, &group)]
+        ),
+        Err(Ok(Error::NestingTooDeep))
+    );
+}
+
+#[test]
+fn test_nested_group_cannot_be_deleted_while_referenced() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let lead = Address::generate(env);
+
+    let team = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&Address::generate(env), 10_000)],
+        1,
+        &token,
+    );
+    let collective = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, child_member(&lead, 10_000, &team)],
+        2,
+        &token,
+    );
+
+    client.deactivate_group(&team, &creator);
+    assert_eq!(
+        client.try_delete_group(&team, &creator),
+        Err(Ok(Error::GroupReferenced))
+    );
+
+    // Deleting the parent releases the child
+    client.deactivate_group(&collective, &creator);
+    client.delete_group(&collective, &creator);
+    client.delete_group(&team, &creator);
+    assert_eq!(client.try_get(&team), Err(Ok(Error::NotFound)));
+}
+
+#[test]
+fn test_removing_child_member_releases_nested_group() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let alice = Address::generate(env);
+
+    let team = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&Address::generate(env), 10_000)],
+        1,
+        &token,
+    );
+    let collective = create_test_group(
+        env,
+        contract,
+        &creator,
+        &vec![env, member(&alice, 10_000)],
+        2,
+        &token,
+    );
+    client.update_members(
+        &collective,
+        &creator,
+        &vec![
+            env,
+            member(&alice, 5000),
+            child_member(&Address::generate(env), 5000, &team),
+        ],
+    );
+    client.update_members(&collective, &creator, &vec![env, member(&alice, 10_000)]);
+
+    client.deactivate_group(&team, &creator);
+    client.delete_group(&team, &creator);
+    assert_eq!(client.try_get(&team), Err(Ok(Error::NotFound)));
+}
@@ -0,0 +1,271 @@
+use super::test_utils::{create_test_group, create_test_members, mint_tokens, setup_test_env};
+use crate::autoshare_logic::{DataKey, GroupList, MAX_PAGE_SIZE};
+use crate::base::errors::Error;
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Vec};
+
+#[test]
+fn test_get_groups_page_walks_all_groups() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    let mut ids = Vec::new(&env);
+    for usages in 1..=5u32 {
+        ids.push_back(create_test_group(
+            &env, &contract, &creator, &members, usages, &token,
+        ));
+    }
+
+    let first = client.get_groups_page(&0, &2);
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.items.get(0).unwrap().id, ids.get(0).unwrap());
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.get_groups_page(&2, &2);
+    assert_eq!(second.items.get(0).unwrap().id, ids.get(2).unwrap());
+    assert_eq!(second.next_cursor, Some(4));
+
+    let last = client.get_groups_page(&4, &2);
+    assert_eq!(last.items.len(), 1);
+    assert_eq!(last.items.get(0).unwrap().id, ids.get(4).unwrap());
+    assert_eq!(last.next_cursor, None);
+}
+
+#[test]
+fn test_get_groups_page_past_end_is_empty() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+    create_test_group(&env, &contract, &creator, &members, 1, &token);
+
+    let page = client.get_groups_page(&10, &5);
+    assert_eq!(page.items.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_page_size_is_bounded() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+
+    assert_eq!(
+        client.try_get_groups_page(&0, &0),
+        Err(Ok(Error::InvalidPageSize))
+    );
+    assert_eq!(
+        client.try_get_groups_page(&0, &(MAX_PAGE_SIZE + 1)),
+        Err(Ok(Error::InvalidPageSize))
+    );
+    assert!(client.try_get_groups_page(&0, &MAX_PAGE_SIZE).is_ok());
+}
+
+#[test]
+fn test_get_groups_by_creator_page_only_returns_creator_groups() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let other = test_env.users.get(1).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    let id1 = create_test_group(&env, &contract, &creator, &members, 1, &token);
+    create_test_group(&env, &contract, &other, &members, 2, &token);
+    let id3 = create_test_group(&env, &contract, &creator, &members, 3, &token);
+
+    let page = client.get_groups_by_creator_page(&creator, &0, &1);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().id, id1);
+    assert_eq!(page.next_cursor, Some(1));
+
+    let page = client.get_groups_by_creator_page(&creator, &1, &1);
+    assert_eq!(page.items.get(0).unwrap().id, id3);
+    assert_eq!(page.next_cursor, None);
+
+    assert_eq!(client.get_groups_by_creator(&creator).len(), 2);
+}
+
+#[test]
+fn test_delete_group_removes_it_from_creator_index() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    let id1 = create_test_group(&env, &contract, &creator, &members, 1, &token);
+    let id2 = create_test_group(&env, &contract, &creator, &members, 2, &token);
+
+    client.deactivate_group(&id1, &creator);
+    client.delete_group(&id1, &creator);
+
+    let page = client.get_groups_by_creator_page(&creator, &0, &10);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().id, id2);
+}
+
+#[test]
+fn test_legacy_group_list_is_served_then_migrated() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    let id1 = create_test_group(&env, &contract, &creator, &members, 1, &token);
+    let id2 = create_test_group(&env, &contract, &creator, &members, 2, &token);
+
+    // Rewrite the list of all groups as the vector earlier versions stored
+    env.as_contract(&contract, || {
+        let storage = env.storage().persistent();
+        for (seq, id) in [&id1, &id2].into_iter().enumerate() {
+            storage.remove(&DataKey::GroupListEntry(GroupList::All, seq as u32));
+            storage.remove(&DataKey::GroupListPosition(GroupList::All, id.clone()));
+        }
+        storage.remove(&DataKey::GroupListCount(GroupList::All));
+        let mut legacy = Vec::new(&env);
+        legacy.push_back(id1.clone());
+        legacy.push_back(id2.clone());
+        storage.set(&DataKey::AllGroups, &legacy);
+    });
+
+    let page = client.get_groups_page(&1, &5);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().id, id2);
+    assert_eq!(page.next_cursor, None);
+
+    // The next group moves the vector into numbered entries
+    let id3 = create_test_group(&env, &contract, &creator, &members, 3, &token);
+    env.as_contract(&contract, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&DataKey::AllGroups));
+        assert_eq!(
+            storage.get::<_, u32>(&DataKey::GroupListCount(GroupList::All)),
+            Some(3)
+        );
+    });
+    let page = client.get_groups_page(&0, &5);
+    assert_eq!(page.items.len(), 3);
+    assert_eq!(page.items.get(0).unwrap().id, id1);
+    assert_eq!(page.items.get(2).unwrap().id, id3);
+}
+
+#[test]
+fn test_reindex_groups_backfills_creator_index() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    create_test_group(&env, &contract, &creator, &members, 1, &token);
+    create_test_group(&env, &contract, &creator, &members, 2, &token);
+    create_test_group(&env, &contract, &creator, &members, 3, &token);
+
+    // Simulate groups created before the creator index existed
+    env.as_contract(&contract, || {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::GroupListCount(GroupList::Creator(
+                creator.clone(),
+            )));
+    });
+    assert_eq!(client.get_groups_by_creator(&creator).len(), 0);
+
+    assert_eq!(client.reindex_groups(&test_env.admin, &0, &2), Some(2));
+    assert_eq!(client.get_groups_by_creator(&creator).len(), 2);
+    assert_eq!(client.reindex_groups(&test_env.admin, &2, &2), None);
+    assert_eq!(client.get_groups_by_creator(&creator).len(), 3);
+
+    // Re-running does not duplicate entries
+    client.reindex_groups(&test_env.admin, &0, &MAX_PAGE_SIZE);
+    assert_eq!(client.get_groups_by_creator(&creator).len(), 3);
+}
+
+#[test]
+fn test_reindex_groups_requires_admin() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let not_admin = Address::generate(&env);
+
+    assert_eq!(
+        client.try_reindex_groups(&not_admin, &0, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_payment_history_pages() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let members = create_test_members(&env, 2);
+
+    let id = create_test_group(&env, &contract, &creator, &members, 1, &token);
+    client.topup_subscription(&id, &2, &token, &creator, &None);
+    client.topup_subscription(&id, &3, &token, &creator, &None);
+
+    let page = client.get_user_payment_history_page(&creator, &0, &2);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items.get(1).unwrap().usages_purchased, 2);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = client.get_group_payment_history_page(&id, &2, &2);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().usages_purchased, 3);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_distribution_history_pages() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let client = AutoShareContractClient::new(&env, &contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let sender = test_env.users.get(1).unwrap().clone();
+    let members = create_test_members(&env, 2);
+    let member = members.get(0).unwrap().address;
+
+    let id = create_test_group(&env, &contract, &creator, &members, 3, &token);
+    mint_tokens(&env, &token, &sender, 600);
+    for amount in [100i128, 200, 300] {
+        client.distribute(&id, &token, &amount, &sender);
+    }
+
+    let page = client.get_group_distributions_page(&id, &1, &5);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items.get(0).unwrap().total_amount, 200);
+    assert_eq!(page.next_cursor, None);
+
+    let page = client.get_member_distributions_page(&member, &0, &2);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let unknown = BytesN::from_array(&env, &[9u8; 32]);
+    let page = client.get_group_distributions_page(&unknown, &0, &5);
+    assert_eq!(page.items.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
@@ -1,7 +1,9 @@
 #![allow(unused_variables)]
 #![allow(unused_imports)]
 
+use crate::base::errors::Error;
 use crate::base::types::GroupMember;
+use crate::test_utils::deploy_autoshare_contract;
 use crate::{AutoShareContract, AutoShareContractClient};
 use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String};
 
@@ -20,11 +22,10 @@ fn create_token_contract<'a>(
 fn test_admin_can_pause() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     assert!(!client.get_paused_status());
     client.pause(&admin);
@@ -35,11 +36,10 @@ fn test_admin_can_pause() {
 fn test_admin_can_unpause() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     client.pause(&admin);
     assert!(client.get_paused_status());
@@ -52,11 +52,10 @@ fn test_admin_can_unpause() {
 fn test_paused_status_returned_correctly() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Initially not paused
     assert!(!client.get_paused_status());
@@ -71,81 +70,72 @@ fn test_paused_status_returned_correctly() {
 }
 
 #[test]
-#[should_panic]
 fn test_non_admin_cannot_pause() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let non_admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
-    client.pause(&non_admin);
+    assert_eq!(client.try_pause(&non_admin), Err(Ok(Error::Unauthorized)));
 }
 
 #[test]
-#[should_panic]
 fn test_non_admin_cannot_unpause() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let non_admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     client.pause(&admin);
-    client.unpause(&non_admin);
+    assert_eq!(client.try_unpause(&non_admin), Err(Ok(Error::Unauthorized)));
 }
 
 #[test]
-#[should_panic]
 fn test_cannot_pause_already_paused() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     client.pause(&admin);
-    client.pause(&admin);
+    assert_eq!(client.try_pause(&admin), Err(Ok(Error::AlreadyPaused)));
 }
 
 #[test]
-#[should_panic]
 fn test_cannot_unpause_not_paused() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
-    client.unpause(&admin);
+    assert_eq!(client.try_unpause(&admin), Err(Ok(Error::NotPaused)));
 }
 
 #[test]
-#[should_panic]
 fn test_create_fails_when_paused() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Setup token
     let token_admin = Address::generate(&env);
     let (token_client, token_admin_client) = create_token_contract(&env, &token_admin);
     let token_address = token_client.address.clone();
     client.add_supported_token(&token_address, &admin);
+    client.set_usage_fee(&token_address, &10, &admin);
 
     client.pause(&admin);
 
@@ -153,25 +143,27 @@ fn test_create_fails_when_paused() {
     let id = BytesN::from_array(&env, &[1u8; 32]);
     let name = String::from_str(&env, "Test Group");
     token_admin_client.mint(&creator, &10000000);
-    client.create(&id, &name, &creator, &100u32, &token_address);
+    assert_eq!(
+        client.try_create(&id, &name, &creator, &100u32, &token_address, &None),
+        Err(Ok(Error::ContractPaused))
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_add_member_fails_when_paused() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Setup token
     let token_admin = Address::generate(&env);
     let (token_client, token_admin_client) = create_token_contract(&env, &token_admin);
     let token_address = token_client.address.clone();
     client.add_supported_token(&token_address, &admin);
+    client.set_usage_fee(&token_address, &10, &admin);
 
     let creator = Address::generate(&env);
     let member = Address::generate(&env);
@@ -179,34 +171,36 @@ fn test_add_member_fails_when_paused() {
     let name = String::from_str(&env, "Test Group");
 
     token_admin_client.mint(&creator, &10000000);
-    client.create(&id, &name, &creator, &100u32, &token_address);
+    client.create(&id, &name, &creator, &100u32, &token_address, &None);
     client.pause(&admin);
-    client.add_group_member(&id, &creator, &member, &50u32);
+    assert_eq!(
+        client.try_add_group_member(&id, &creator, &member, &5000u32),
+        Err(Ok(Error::ContractPaused))
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_topup_subscription_fails_when_paused() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Setup token
     let token_admin = Address::generate(&env);
     let (token_client, token_admin_client) = create_token_contract(&env, &token_admin);
     let token_address = token_client.address.clone();
     client.add_supported_token(&token_address, &admin);
+    client.set_usage_fee(&token_address, &10, &admin);
 
     let creator = Address::generate(&env);
     let id = BytesN::from_array(&env, &[1u8; 32]);
     let name = String::from_str(&env, "Test Group");
 
     token_admin_client.mint(&creator, &10000000);
-    client.create(&id, &name, &creator, &100u32, &token_address);
+    client.create(&id, &name, &creator, &100u32, &token_address, &None);
 
     // Pause the contract
     client.pause(&admin);
@@ -214,31 +208,34 @@ fn test_topup_subscription_fails_when_paused() {
     // Attempt to top up while paused - should fail with ContractPaused
     let payer = Address::generate(&env);
     token_admin_client.mint(&payer, &10000000);
-    client.topup_subscription(&id, &10u32, &token_address, &payer);
+    assert_eq!(
+        client.try_topup_subscription(&id, &10u32, &token_address, &payer, &None),
+        Err(Ok(Error::ContractPaused))
+    );
 }
 
 #[test]
 fn test_read_functions_work_when_paused() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Setup token
     let token_admin = Address::generate(&env);
     let (token_client, token_admin_client) = create_token_contract(&env, &token_admin);
     let token_address = token_client.address.clone();
     client.add_supported_token(&token_address, &admin);
+    client.set_usage_fee(&token_address, &10, &admin);
 
     let creator = Address::generate(&env);
     let id = BytesN::from_array(&env, &[1u8; 32]);
     let name = String::from_str(&env, "Test Group");
 
     token_admin_client.mint(&creator, &10000000);
-    client.create(&id, &name, &creator, &100u32, &token_address);
+    client.create(&id, &name, &creator, &100u32, &token_address, &None);
     client.pause(&admin);
 
     // These should all work while paused
@@ -254,17 +251,17 @@ fn test_read_functions_work_when_paused() {
 fn test_operations_work_after_unpause() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register(AutoShareContract, ());
-    let client = AutoShareContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    client.initialize_admin(&admin);
+    let contract_id = deploy_autoshare_contract(&env, &admin);
+    let client = AutoShareContractClient::new(&env, &contract_id);
 
     // Setup token
     let token_admin = Address::generate(&env);
     let (token_client, token_admin_client) = create_token_contract(&env, &token_admin);
     let token_address = token_client.address.clone();
     client.add_supported_token(&token_address, &admin);
+    client.set_usage_fee(&token_address, &10, &admin);
 
     client.pause(&admin);
     client.unpause(&admin);
@@ -275,7 +272,7 @@ fn test_operations_work_after_unpause() {
 
     token_admin_client.mint(&creator, &10000000);
     // Should work after unpause
-    client.create(&id, &name, &creator, &100u32, &token_address);
+    client.create(&id, &name, &creator, &100u32, &token_address, &None);
     let result = client.get(&id);
     assert_eq!(result.name, name);
 }
@@ -0,0 +1,188 @@
+use super::test_utils::{mint_tokens, setup_test_env, TestEnv};
+use crate::base::errors::Error;
+use crate::base::types::{AdminAction, PricingTier};
+use crate::mock_token::MockTokenClient;
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env, String, Vec};
+
+/// 10% off from 100 usages, 25% off from 1000.
+fn volume_tiers(env: &Env) -> Vec<PricingTier> {
+    vec![
+        env,
+        PricingTier {
+            min_usages: 100,
+            discount_bps: 1000,
+        },
+        PricingTier {
+            min_usages: 1000,
+            discount_bps: 2500,
+        },
+    ]
+}
+
+fn set_volume_tiers(test_env: &TestEnv, client: &AutoShareContractClient) {
+    client.set_pricing_tiers(&test_env.admin, &volume_tiers(&test_env.env));
+}
+
+#[test]
+fn test_quote_applies_highest_tier_reached() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    assert_eq!(client.quote_usages(&token, &100), 1000);
+
+    set_volume_tiers(&test_env, &client);
+    assert_eq!(client.get_pricing_tiers(), volume_tiers(env));
+    assert_eq!(client.quote_usages(&token, &99), 990);
+    assert_eq!(client.quote_usages(&token, &100), 900);
+    assert_eq!(client.quote_usages(&token, &999), 8991);
+    assert_eq!(client.quote_usages(&token, &1000), 7500);
+}
+
+#[test]
+fn test_quote_validation() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    assert_eq!(
+        client.try_quote_usages(&token, &0),
+        Err(Ok(Error::InvalidUsageCount))
+    );
+    assert_eq!(
+        client.try_quote_usages(&Address::generate(env), &10),
+        Err(Ok(Error::UnsupportedToken))
+    );
+}
+
+#[test]
+fn test_purchases_charge_quote_and_record_discount() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let token_client = MockTokenClient::new(env, &token);
+    let creator = test_env.users.get(0).unwrap();
+    set_volume_tiers(&test_env, &client);
+
+    mint_tokens(env, &token, &creator, 20_000);
+    let id = BytesN::from_array(env, &[1u8; 32]);
+    let quote = client.quote_usages(&token, &1000);
+    client.create(
+        &id,
+        &String::from_str(env, "Bulk"),
+        &creator,
+        &1000,
+        &token,
+        &None,
+    );
+    assert_eq!(token_client.balance(&creator), 20_000 - quote);
+
+    client.topup_subscription(&id, &100, &token, &creator, &None);
+    client.topup_subscription(&id, &5, &token, &creator, &None);
+    assert_eq!(token_client.balance(&creator), 20_000 - 7500 - 900 - 50);
+
+    let history = client.get_group_payment_history(&id);
+    assert_eq!(history.get(0).unwrap().amount_paid, 7500);
+    assert_eq!(history.get(0).unwrap().discount_bps, 2500);
+    assert_eq!(history.get(1).unwrap().amount_paid, 900);
+    assert_eq!(history.get(1).unwrap().discount_bps, 1000);
+    assert_eq!(history.get(2).unwrap().discount_bps, 0);
+}
+
+#[test]
+fn test_refund_uses_discounted_price() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let token_client = MockTokenClient::new(env, &token);
+    let creator = test_env.users.get(0).unwrap();
+    set_volume_tiers(&test_env, &client);
+
+    mint_tokens(env, &token, &creator, 900);
+    let id = BytesN::from_array(env, &[1u8; 32]);
+    client.create(
+        &id,
+        &String::from_str(env, "Bulk"),
+        &creator,
+        &100,
+        &token,
+        &None,
+    );
+    assert_eq!(token_client.balance(&creator), 0);
+
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+
+    assert_eq!(token_client.balance(&creator), 900);
+    let refund = client.get_group_payment_history(&id).get(1).unwrap();
+    assert_eq!(refund.amount_paid, -900);
+    assert_eq!(refund.discount_bps, 1000);
+}
+
+#[test]
+fn test_set_pricing_tiers_validation() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+
+    let unordered = vec![
+        env,
+        PricingTier {
+            min_usages: 1000,
+            discount_bps: 2500,
+        },
+        PricingTier {
+            min_usages: 100,
+            discount_bps: 1000,
+        },
+    ];
+    assert_eq!(
+        client.try_set_pricing_tiers(&test_env.admin, &unordered),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    let free = vec![
+        env,
+        PricingTier {
+            min_usages: 10,
+            discount_bps: 10_000,
+        },
+    ];
+    assert_eq!(
+        client.try_set_pricing_tiers(&test_env.admin, &free),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    assert_eq!(
+        client.try_set_pricing_tiers(&Address::generate(env), &volume_tiers(env)),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // An empty list removes all discounts
+    set_volume_tiers(&test_env, &client);
+    client.set_pricing_tiers(&test_env.admin, &Vec::new(env));
+    assert_eq!(client.get_pricing_tiers().len(), 0);
+}
+
+#[test]
+fn test_pricing_tiers_are_timelocked() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    client.set_timelock_delay(&test_env.admin, &3600);
+
+    assert_eq!(
+        client.try_set_pricing_tiers(&test_env.admin, &volume_tiers(env)),
+        Err(Ok(Error::TimelockActive))
+    );
+    let action_id = client.queue_action(
+        &test_env.admin,
+        &AdminAction::SetPricingTiers(volume_tiers(env)),
+    );
+    assert_eq!(client.get_queued_action(&action_id).eta, 3600);
+}
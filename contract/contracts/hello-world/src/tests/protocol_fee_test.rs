@@ -0,0 +1,223 @@
+use super::test_utils::{assert_balance, create_test_group, mint_tokens, setup_test_env, TestEnv};
+use crate::base::errors::Error;
+use crate::base::types::{AdminAction, FeeMode, GroupMember};
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN};
+
+/// Creates a one-usage group split 25/75 and switches it to percentage mode
+/// under a 2% protocol fee. Returns the group id.
+fn create_percentage_group(
+    test_env: &TestEnv,
+    client: &AutoShareContractClient,
+    member1: &Address,
+    member2: &Address,
+) -> BytesN<32> {
+    let env = &test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let members = vec![
+        env,
+        GroupMember {
+            address: member1.clone(),
+            percentage: 2500,
+            fixed_amount: 0,
+            fixed_token: None,
+            group: None,
+        },
+        GroupMember {
+            address: member2.clone(),
+            percentage: 7500,
+            fixed_amount: 0,
+            fixed_token: None,
+            group: None,
+        },
+    ];
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &members,
+        1,
+        &token,
+    );
+    client.set_protocol_fee(&test_env.admin, &200);
+    client.set_fee_mode(&id, &creator, &FeeMode::Percentage);
+    id
+}
+
+#[test]
+fn test_set_protocol_fee() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+
+    assert_eq!(client.get_protocol_fee(), 0);
+    client.set_protocol_fee(&test_env.admin, &250);
+    assert_eq!(client.get_protocol_fee(), 250);
+
+    assert_eq!(
+        client.try_set_protocol_fee(&test_env.admin, &10_000),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_protocol_fee(&Address::generate(env), &100),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_protocol_fee_is_timelocked() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+    client.set_timelock_delay(&test_env.admin, &3600);
+
+    assert_eq!(
+        client.try_set_protocol_fee(&test_env.admin, &100),
+        Err(Ok(Error::TimelockActive))
+    );
+    client.queue_action(&test_env.admin, &AdminAction::SetProtocolFee(100));
+}
+
+#[test]
+fn test_fee_mode_defaults_to_prepaid_and_is_creator_only() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &vec![env],
+        1,
+        &token,
+    );
+
+    assert_eq!(client.get_fee_mode(&id), FeeMode::Prepaid);
+    assert_eq!(
+        client.try_set_fee_mode(&id, &Address::generate(env), &FeeMode::Percentage),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_get_fee_mode(&BytesN::from_array(env, &[9u8; 32])),
+        Err(Ok(Error::NotFound))
+    );
+}
+
+#[test]
+fn test_percentage_mode_deducts_fee_before_shares() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+    let id = create_percentage_group(&test_env, &client, &member1, &member2);
+    let revenue_before = client.get_fee_revenue(&token);
+
+    let preview = client.preview_distribution(&id, &token, &1000);
+    assert_eq!(preview.get(0).unwrap().amount, 245);
+    assert_eq!(preview.get(1).unwrap().amount, 735);
+
+    mint_tokens(env, &token, &sender, 1000);
+    client.distribute(&id, &token, &1000, &sender);
+
+    assert_balance(env, &token, &member1, 245);
+    assert_balance(env, &token, &member2, 735);
+    assert_eq!(client.get_fee_revenue(&token), revenue_before + 20);
+
+    let dist = client.get_group_distributions(&id).get(0).unwrap();
+    assert_eq!(dist.total_amount, 1000);
+    assert_eq!(dist.fee_amount, 20);
+}
+
+#[test]
+fn test_percentage_mode_does_not_need_usages() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let id = create_percentage_group(
+        &test_env,
+        &client,
+        &Address::generate(env),
+        &Address::generate(env),
+    );
+
+    mint_tokens(env, &token, &sender, 3000);
+    for _ in 0..3 {
+        client.distribute(&id, &token, &1000, &sender);
+    }
+    assert_eq!(client.get_remaining_usages(&id), 1);
+    let history = client.get_group_distributions(&id);
+    assert_eq!(history.get(1).unwrap().distribution_number, 1);
+    assert_eq!(history.get(2).unwrap().distribution_number, 2);
+
+    // Back in prepaid mode the remaining usage is spent and no fee is taken
+    client.set_fee_mode(&id, &creator, &FeeMode::Prepaid);
+    mint_tokens(env, &token, &sender, 1000);
+    client.distribute(&id, &token, &1000, &sender);
+    assert_eq!(client.get_remaining_usages(&id), 0);
+    assert_eq!(
+        client
+            .get_group_distributions(&id)
+            .get(3)
+            .unwrap()
+            .fee_amount,
+        0
+    );
+    assert_eq!(
+        client.try_distribute(&id, &token, &1000, &sender),
+        Err(Ok(Error::NoUsagesRemaining))
+    );
+}
+
+#[test]
+fn test_percentage_mode_needs_a_protocol_fee() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &vec![
+            env,
+            GroupMember {
+                address: Address::generate(env),
+                percentage: 10_000,
+                fixed_amount: 0,
+                fixed_token: None,
+                group: None,
+            },
+        ],
+        1,
+        &token,
+    );
+
+    assert_eq!(
+        client.try_set_fee_mode(&id, &creator, &FeeMode::Percentage),
+        Err(Ok(Error::ProtocolFeeNotSet))
+    );
+    assert_eq!(client.get_fee_mode(&id), FeeMode::Prepaid);
+
+    // A fee dropped back to 0 does not make percentage groups free to use
+    client.set_protocol_fee(&test_env.admin, &200);
+    client.set_fee_mode(&id, &creator, &FeeMode::Percentage);
+    client.set_protocol_fee(&test_env.admin, &0);
+
+    mint_tokens(env, &token, &sender, 2000);
+    client.distribute(&id, &token, &1000, &sender);
+    assert_eq!(client.get_remaining_usages(&id), 0);
+    assert_eq!(
+        client.try_distribute(&id, &token, &1000, &sender),
+        Err(Ok(Error::NoUsagesRemaining))
+    );
+}
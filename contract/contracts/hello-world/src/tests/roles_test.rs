@@ -0,0 +1,183 @@
+use super::test_utils::{accrue_fee_revenue, setup_test_env};
+use crate::base::errors::Error;
+use crate::base::types::Role;
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, Address};
+
+#[test]
+fn test_grant_role() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let pauser = Address::generate(&env);
+
+    assert!(!client.has_role(&Role::Pauser, &pauser));
+    client.grant_role(&test_env.admin, &Role::Pauser, &pauser);
+
+    assert!(client.has_role(&Role::Pauser, &pauser));
+    assert!(!client.has_role(&Role::Treasurer, &pauser));
+    let members = client.get_role_members(&Role::Pauser);
+    assert_eq!(members.len(), 1);
+    assert_eq!(members.get(0).unwrap(), pauser);
+}
+
+#[test]
+fn test_grant_role_twice_fails() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let pauser = Address::generate(&env);
+
+    client.grant_role(&test_env.admin, &Role::Pauser, &pauser);
+    assert_eq!(
+        client.try_grant_role(&test_env.admin, &Role::Pauser, &pauser),
+        Err(Ok(Error::AlreadyExists))
+    );
+}
+
+#[test]
+fn test_non_admin_cannot_grant_or_revoke() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let pauser = Address::generate(&env);
+
+    assert_eq!(
+        client.try_grant_role(&pauser, &Role::Pauser, &pauser),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.grant_role(&test_env.admin, &Role::Pauser, &pauser);
+    assert_eq!(
+        client.try_revoke_role(&pauser, &Role::Pauser, &pauser),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_revoke_role() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let pauser = Address::generate(&env);
+
+    client.grant_role(&test_env.admin, &Role::Pauser, &pauser);
+    client.revoke_role(&test_env.admin, &Role::Pauser, &pauser);
+
+    assert!(!client.has_role(&Role::Pauser, &pauser));
+    assert_eq!(client.get_role_members(&Role::Pauser).len(), 0);
+    assert_eq!(client.try_pause(&pauser), Err(Ok(Error::Unauthorized)));
+    assert_eq!(
+        client.try_revoke_role(&test_env.admin, &Role::Pauser, &pauser),
+        Err(Ok(Error::NotFound))
+    );
+}
+
+#[test]
+fn test_pauser_can_pause_and_unpause() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let pauser = Address::generate(&env);
+
+    client.grant_role(&test_env.admin, &Role::Pauser, &pauser);
+
+    client.pause(&pauser);
+    assert!(client.get_paused_status());
+    client.unpause(&pauser);
+    assert!(!client.get_paused_status());
+}
+
+#[test]
+fn test_roles_are_scoped_to_their_duty() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let pauser = Address::generate(&env);
+
+    client.grant_role(&test_env.admin, &Role::Pauser, &pauser);
+
+    assert_eq!(
+        client.try_set_usage_fee(&token, &25, &pauser),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_supported_token(&Address::generate(&env), &pauser),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_withdraw(&pauser, &token, &1, &pauser),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_grant_role(&pauser, &Role::Treasurer, &pauser),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_fee_manager_can_set_usage_fee() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let fee_manager = Address::generate(&env);
+
+    client.grant_role(&test_env.admin, &Role::FeeManager, &fee_manager);
+    client.set_usage_fee(&token, &25, &fee_manager);
+
+    assert_eq!(client.get_usage_fee(&token), 25);
+    assert_eq!(client.try_pause(&fee_manager), Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_token_manager_can_manage_tokens() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let token_manager = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    client.grant_role(&test_env.admin, &Role::TokenManager, &token_manager);
+
+    client.add_supported_token(&token, &token_manager);
+    assert!(client.is_token_supported(&token));
+    client.remove_supported_token(&token, &token_manager);
+    assert!(!client.is_token_supported(&token));
+}
+
+#[test]
+fn test_treasurer_can_withdraw() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let contract = test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(&env, &contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let treasurer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    accrue_fee_revenue(&env, &contract, &token, 100);
+    client.grant_role(&test_env.admin, &Role::Treasurer, &treasurer);
+    client.withdraw(&treasurer, &token, &400, &recipient);
+
+    assert_eq!(client.get_contract_balance(&token), 600);
+    assert_eq!(
+        crate::mock_token::MockTokenClient::new(&env, &token).balance(&recipient),
+        400
+    );
+}
+
+#[test]
+fn test_admin_passes_every_role_check() {
+    let test_env = setup_test_env();
+    let env = test_env.env;
+    let client = AutoShareContractClient::new(&env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    assert!(!client.has_role(&Role::Pauser, &test_env.admin));
+    client.pause(&test_env.admin);
+    client.unpause(&test_env.admin);
+    client.set_usage_fee(&token, &30, &test_env.admin);
+    assert_eq!(client.get_usage_fee(&token), 30);
+}
@@ -0,0 +1,385 @@
+use super::test_utils::{assert_balance, create_test_group, mint_tokens, setup_test_env, TestEnv};
+use crate::autoshare_logic::DataKey;
+use crate::base::errors::Error;
+use crate::base::types::{DistributionMode, FeeMode, GroupMember};
+use crate::AutoShareContractClient;
+use group_splitter::{GroupSplitter, GroupSplitterClient};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, String, Vec};
+
+// Built separately for wasm32v1-none; see build.rs
+#[cfg(splitter_wasm)]
+mod splitter_wasm {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/group_splitter.wasm");
+}
+
+/// Uploads the built splitter WASM and configures its hash.
+#[cfg(splitter_wasm)]
+fn configure_splitter_wasm(test_env: &TestEnv) {
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let wasm_hash = env.deployer().upload_contract_wasm(splitter_wasm::WASM);
+    client.set_splitter_wasm_hash(&test_env.admin, &wasm_hash);
+}
+
+/// Registers a splitter natively and records it for the group, standing in for
+/// the deployment `create` performs from the configured WASM hash.
+fn attach_splitter(test_env: &TestEnv, id: &BytesN<32>) -> Address {
+    let env = &test_env.env;
+    let splitter = env.register(
+        GroupSplitter,
+        (test_env.autoshare_contract.clone(), id.clone()),
+    );
+    env.as_contract(&test_env.autoshare_contract, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::GroupSplitter(id.clone()), &splitter);
+    });
+    splitter
+}
+
+fn create_split_group(test_env: &TestEnv, member1: &Address, member2: &Address) -> BytesN<32> {
+    let env = &test_env.env;
+    let mut members = Vec::new(env);
+    members.push_back(GroupMember {
+        address: member1.clone(),
+        percentage: 2500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    members.push_back(GroupMember {
+        address: member2.clone(),
+        percentage: 7500,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let creator = test_env.users.get(0).unwrap().clone();
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &members,
+        1,
+        &token,
+    )
+}
+
+#[test]
+fn test_set_and_get_splitter_wasm_hash() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let wasm_hash = BytesN::from_array(&test_env.env, &[3u8; 32]);
+    client.set_splitter_wasm_hash(&test_env.admin, &wasm_hash);
+    assert_eq!(client.get_splitter_wasm_hash(), wasm_hash);
+}
+
+#[test]
+fn test_non_admin_cannot_set_splitter_wasm_hash() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let non_admin = Address::generate(&test_env.env);
+    let wasm_hash = BytesN::from_array(&test_env.env, &[3u8; 32]);
+    assert_eq!(
+        client.try_set_splitter_wasm_hash(&non_admin, &wasm_hash),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_deploy_group_splitter_requires_wasm_hash() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let id = create_split_group(
+        &test_env,
+        &Address::generate(&test_env.env),
+        &Address::generate(&test_env.env),
+    );
+    let creator = test_env.users.get(0).unwrap().clone();
+    assert_eq!(
+        client.try_deploy_group_splitter(&id, &creator),
+        Err(Ok(Error::SplitterNotConfigured))
+    );
+}
+
+#[test]
+fn test_group_without_splitter_has_no_registry_entry() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    let id = create_split_group(
+        &test_env,
+        &Address::generate(&test_env.env),
+        &Address::generate(&test_env.env),
+    );
+    assert_eq!(client.try_get_group_splitter(&id), Err(Ok(Error::NotFound)));
+}
+
+#[test]
+#[cfg(splitter_wasm)]
+fn test_create_deploys_splitter_from_wasm_hash() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    configure_splitter_wasm(&test_env);
+
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+    let id = create_split_group(&test_env, &member1, &member2);
+
+    // The splitter is deployed by the contract with the group id as salt
+    let splitter = client.get_group_splitter(&id);
+    let expected = env
+        .deployer()
+        .with_address(test_env.autoshare_contract.clone(), id.clone())
+        .deployed_address();
+    assert_eq!(splitter, expected);
+    let splitter_client = GroupSplitterClient::new(env, &splitter);
+    assert_eq!(splitter_client.group_id(), id);
+    assert_eq!(splitter_client.factory(), test_env.autoshare_contract);
+
+    mint_tokens(env, &token, &splitter, 1000);
+    assert_eq!(client.settle(&id, &token), 1000);
+    assert_balance(env, &token, &member1, 250);
+    assert_balance(env, &token, &member2, 750);
+    assert_balance(env, &token, &splitter, 0);
+}
+
+#[test]
+#[cfg(splitter_wasm)]
+fn test_deploy_group_splitter_for_existing_group() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let id = create_split_group(&test_env, &Address::generate(env), &Address::generate(env));
+    configure_splitter_wasm(&test_env);
+
+    assert_eq!(
+        client.try_deploy_group_splitter(&id, &Address::generate(env)),
+        Err(Ok(Error::Unauthorized))
+    );
+    let splitter = client.deploy_group_splitter(&id, &creator);
+    assert_eq!(client.get_group_splitter(&id), splitter);
+    assert_eq!(GroupSplitterClient::new(env, &splitter).group_id(), id);
+    assert_eq!(
+        client.try_deploy_group_splitter(&id, &creator),
+        Err(Ok(Error::AlreadyExists))
+    );
+}
+
+#[test]
+fn test_settle_splits_deposited_balance() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+    let id = create_split_group(&test_env, &member1, &member2);
+    let splitter = attach_splitter(&test_env, &id);
+
+    assert_eq!(client.get_group_splitter(&id), splitter);
+    let splitter_client = GroupSplitterClient::new(env, &splitter);
+    assert_eq!(splitter_client.group_id(), id);
+    assert_eq!(splitter_client.factory(), test_env.autoshare_contract);
+
+    // A payer simply transfers tokens to the splitter's address
+    mint_tokens(env, &token, &splitter, 1001);
+
+    assert_eq!(client.get_unsettled_balance(&id, &token), 1001);
+    assert_eq!(client.settle(&id, &token), 1001);
+    assert_balance(env, &token, &member1, 250);
+    assert_balance(env, &token, &member2, 751);
+    assert_balance(env, &token, &splitter, 0);
+    assert_eq!(client.get_unsettled_balance(&id, &token), 0);
+
+    // Settling consumes a usage and is recorded as a deposit from the splitter
+    assert_eq!(client.get_remaining_usages(&id), 0);
+    let history = client.get_group_distributions(&id);
+    assert_eq!(history.len(), 1);
+    let dist = history.get(0).unwrap();
+    assert!(dist.is_deposit);
+    assert_eq!(dist.sender, splitter);
+    assert_eq!(dist.total_amount, 1001);
+    assert_eq!(dist.member_amounts.len(), 2);
+    assert_eq!(client.get_member_distributions(&member2).len(), 1);
+}
+
+#[test]
+fn test_settle_in_claim_mode_credits_members() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+    let id = create_split_group(&test_env, &member1, &member2);
+    let splitter = attach_splitter(&test_env, &id);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    client.set_distribution_mode(&id, &creator, &DistributionMode::Claim);
+
+    mint_tokens(env, &token, &splitter, 400);
+    client.settle(&id, &token);
+
+    assert_balance(env, &token, &splitter, 0);
+    assert_eq!(client.get_claimable(&member1, &token), 100);
+    assert_eq!(client.get_claimable(&member2, &token), 300);
+
+    client.claim(&member2, &token);
+    assert_balance(env, &token, &member2, 300);
+}
+
+#[test]
+fn test_settle_in_percentage_mode_takes_protocol_fee() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+    let id = create_split_group(&test_env, &member1, &member2);
+    let splitter = attach_splitter(&test_env, &id);
+
+    let creator = test_env.users.get(0).unwrap().clone();
+    client.set_protocol_fee(&test_env.admin, &100);
+    client.set_fee_mode(&id, &creator, &FeeMode::Percentage);
+    let revenue_before = client.get_fee_revenue(&token);
+
+    mint_tokens(env, &token, &splitter, 1000);
+    client.settle(&id, &token);
+
+    assert_balance(env, &token, &member1, 247);
+    assert_balance(env, &token, &member2, 743);
+    assert_balance(env, &token, &splitter, 0);
+    assert_eq!(client.get_fee_revenue(&token), revenue_before + 10);
+    assert_eq!(client.get_remaining_usages(&id), 1);
+    assert_eq!(
+        client
+            .get_group_distributions(&id)
+            .get(0)
+            .unwrap()
+            .fee_amount,
+        10
+    );
+}
+
+#[test]
+fn test_settle_fails_when_empty() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+
+    let id = create_split_group(
+        &test_env,
+        &Address::generate(&test_env.env),
+        &Address::generate(&test_env.env),
+    );
+    attach_splitter(&test_env, &id);
+
+    assert_eq!(
+        client.try_settle(&id, &token),
+        Err(Ok(Error::NothingToSplit))
+    );
+}
+
+#[test]
+fn test_settle_fails_without_usages() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+
+    let id = create_split_group(&test_env, &Address::generate(env), &Address::generate(env));
+    let splitter = attach_splitter(&test_env, &id);
+
+    mint_tokens(env, &token, &splitter, 100);
+    client.settle(&id, &token);
+
+    // The group was bought with a single usage
+    mint_tokens(env, &token, &splitter, 100);
+    assert_eq!(
+        client.try_settle(&id, &token),
+        Err(Ok(Error::NoUsagesRemaining))
+    );
+}
+
+#[test]
+fn test_settle_passes_child_share_to_child_group() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let creator = test_env.users.get(0).unwrap().clone();
+
+    let team_member = Address::generate(env);
+    let mut team_members = Vec::new(env);
+    team_members.push_back(GroupMember {
+        address: team_member.clone(),
+        percentage: 10_000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    });
+    let team = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &team_members,
+        2,
+        &token,
+    );
+
+    let member1 = Address::generate(env);
+    let id = create_split_group(&test_env, &member1, &Address::generate(env));
+    let mut members = client.get_group_members(&id);
+    let mut nested = members.get(1).unwrap();
+    nested.group = Some(team.clone());
+    members.set(1, nested);
+    client.update_members(&id, &creator, &members);
+    let splitter = attach_splitter(&test_env, &id);
+
+    mint_tokens(env, &token, &splitter, 1000);
+    client.settle(&id, &token);
+
+    assert_balance(env, &token, &member1, 250);
+    assert_balance(env, &token, &team_member, 750);
+    assert_balance(env, &token, &splitter, 0);
+    assert_eq!(client.get_remaining_usages(&team), 1);
+
+    let child_dist = client.get_group_distributions(&team).get(0).unwrap();
+    assert_eq!(child_dist.parent_group, Some(id));
+    assert_eq!(child_dist.sender, splitter);
+}
+
+#[test]
+fn test_deleted_group_with_splitter_cannot_be_recreated() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap().clone();
+    let other = test_env.users.get(1).unwrap().clone();
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let id = create_split_group(&test_env, &Address::generate(env), &Address::generate(env));
+    let splitter = attach_splitter(&test_env, &id);
+
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+
+    mint_tokens(env, &token, &other, 1000);
+    let name = String::from_str(env, "Takeover");
+    assert_eq!(
+        client.try_create(&id, &name, &other, &1, &token, &None),
+        Err(Ok(Error::AlreadyExists))
+    );
+    assert_eq!(client.get_group_splitter(&id), splitter);
+}
@@ -0,0 +1,190 @@
+use super::test_utils::{mint_tokens, setup_test_env, TestEnv};
+use crate::base::errors::Error;
+use crate::base::types::GroupMember;
+use crate::AutoShareContractClient;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, String,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+const MONTH_PRICE: i128 = 500;
+
+/// Offers a 30-day plan and creates a group paid by that plan alone, with one
+/// member taking everything. Returns the group id.
+fn create_plan_group(test_env: &TestEnv, client: &AutoShareContractClient) -> BytesN<32> {
+    let env = &test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    client.set_subscription_plan(&test_env.admin, &token, &MONTH, &Some(MONTH_PRICE));
+
+    mint_tokens(env, &token, &creator, 10_000);
+    let id = BytesN::from_array(env, &[1u8; 32]);
+    client.create(
+        &id,
+        &String::from_str(env, "Monthly"),
+        &creator,
+        &0,
+        &token,
+        &Some(MONTH),
+    );
+    let members = vec![
+        env,
+        GroupMember {
+            address: test_env.users.get(1).unwrap(),
+            percentage: 10000,
+            fixed_amount: 0,
+            fixed_token: None,
+            group: None,
+        },
+    ];
+    client.update_members(&id, &creator, &members);
+    id
+}
+
+#[test]
+fn test_set_and_remove_subscription_plan() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    assert_eq!(client.get_subscription_plan(&token, &MONTH), None);
+    client.set_subscription_plan(&test_env.admin, &token, &MONTH, &Some(MONTH_PRICE));
+    assert_eq!(
+        client.get_subscription_plan(&token, &MONTH),
+        Some(MONTH_PRICE)
+    );
+
+    client.set_subscription_plan(&test_env.admin, &token, &MONTH, &None);
+    assert_eq!(client.get_subscription_plan(&token, &MONTH), None);
+    assert_eq!(
+        client.try_set_subscription_plan(&test_env.admin, &token, &MONTH, &None),
+        Err(Ok(Error::SubscriptionPlanNotFound))
+    );
+}
+
+#[test]
+fn test_set_subscription_plan_validation() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let admin = &test_env.admin;
+
+    assert_eq!(
+        client.try_set_subscription_plan(admin, &token, &0, &Some(MONTH_PRICE)),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_subscription_plan(admin, &token, &MONTH, &Some(0)),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_subscription_plan(admin, &Address::generate(env), &MONTH, &Some(1)),
+        Err(Ok(Error::UnsupportedToken))
+    );
+    assert_eq!(
+        client.try_set_subscription_plan(&Address::generate(env), &token, &MONTH, &Some(1)),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_create_with_plan_sets_expiry_and_charges_price() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    env.ledger().set_timestamp(1_000);
+
+    let id = create_plan_group(&test_env, &client);
+
+    assert_eq!(client.get_subscription_expiry(&id), 1_000 + MONTH);
+    assert_eq!(client.get_remaining_usages(&id), 0);
+    assert_eq!(client.get_fee_revenue(&token), MONTH_PRICE);
+
+    let history = client.get_group_payment_history(&id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().usages_purchased, 0);
+    assert_eq!(history.get(0).unwrap().amount_paid, MONTH_PRICE);
+}
+
+#[test]
+fn test_active_subscription_distributes_without_spending_usages() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    env.ledger().set_timestamp(1_000);
+
+    let id = create_plan_group(&test_env, &client);
+    client.topup_subscription(&id, &1, &token, &creator, &None);
+
+    for _ in 0..3 {
+        client.distribute(&id, &token, &100, &creator);
+    }
+    assert_eq!(client.get_remaining_usages(&id), 1);
+
+    // Once the plan lapses, distributions spend the remaining usage
+    env.ledger().set_timestamp(1_000 + MONTH);
+    client.distribute(&id, &token, &100, &creator);
+    assert_eq!(client.get_remaining_usages(&id), 0);
+    assert_eq!(
+        client.try_distribute(&id, &token, &100, &creator),
+        Err(Ok(Error::NoUsagesRemaining))
+    );
+
+    // Distributions are numbered in order whether or not they spent a usage
+    let history = client.get_group_distributions(&id);
+    for (number, dist) in history.iter().enumerate() {
+        assert_eq!(dist.distribution_number, number as u32);
+    }
+}
+
+#[test]
+fn test_topup_extends_subscription() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    env.ledger().set_timestamp(1_000);
+
+    let id = create_plan_group(&test_env, &client);
+
+    // An active plan is extended from its current expiry
+    client.topup_subscription(&id, &0, &token, &creator, &Some(MONTH));
+    assert_eq!(client.get_subscription_expiry(&id), 1_000 + 2 * MONTH);
+
+    // A lapsed plan restarts from now
+    env.ledger().set_timestamp(10 * MONTH);
+    client.topup_subscription(&id, &0, &token, &creator, &Some(MONTH));
+    assert_eq!(client.get_subscription_expiry(&id), 11 * MONTH);
+    assert_eq!(client.get_fee_revenue(&token), 3 * MONTH_PRICE);
+}
+
+#[test]
+fn test_purchase_validation() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let id = BytesN::from_array(env, &[2u8; 32]);
+    let name = String::from_str(env, "Plan");
+
+    assert_eq!(
+        client.try_create(&id, &name, &creator, &0, &token, &Some(MONTH)),
+        Err(Ok(Error::SubscriptionPlanNotFound))
+    );
+    assert_eq!(
+        client.try_create(&id, &name, &creator, &0, &token, &None),
+        Err(Ok(Error::InvalidUsageCount))
+    );
+    assert_eq!(
+        client.try_get_subscription_expiry(&id),
+        Err(Ok(Error::NotFound))
+    );
+}
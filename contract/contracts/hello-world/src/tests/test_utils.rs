@@ -1,3 +1,4 @@
+use crate::autoshare_logic::TOTAL_BASIS_POINTS;
 use crate::mock_token::{MockToken, MockTokenClient};
 use crate::{AutoShareContract, AutoShareContractClient};
 use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String, Vec};
@@ -17,11 +18,6 @@ pub fn setup_test_env() -> TestEnv {
     let admin = create_test_admin(&env);
     let users = create_test_users(&env, 3); // Default 3 users
 
-    // Deploy AutoShare contract
-    let contract_id = deploy_autoshare_contract(&env, &admin);
-    let client = AutoShareContractClient::new(&env, &contract_id);
-    client.initialize_admin(&admin);
-
     // Deploy a mock token
     let token_id = deploy_mock_token(
         &env,
@@ -31,8 +27,13 @@ pub fn setup_test_env() -> TestEnv {
     let mut mock_tokens = Vec::new(&env);
     mock_tokens.push_back(token_id.clone());
 
-    // Enable the mock token
-    client.add_supported_token(&token_id, &admin);
+    // Deploy AutoShare contract with the mock token enabled
+    let mut usage_fees = Vec::new(&env);
+    usage_fees.push_back(crate::base::types::TokenAmount {
+        token: token_id.clone(),
+        amount: DEFAULT_USAGE_FEE,
+    });
+    let contract_id = env.register(AutoShareContract, (&admin, usage_fees));
 
     TestEnv {
         env,
@@ -89,8 +90,15 @@ pub fn approve_tokens(
     // We will leave it as a placeholder.
 }
 
-pub fn deploy_autoshare_contract(env: &Env, _admin: &Address) -> Address {
-    env.register(AutoShareContract, ())
+/// Usage fee `setup_test_env` sets for its mock token.
+pub const DEFAULT_USAGE_FEE: i128 = 10;
+
+/// Deploys the contract with `admin` and no supported tokens.
+pub fn deploy_autoshare_contract(env: &Env, admin: &Address) -> Address {
+    env.register(
+        AutoShareContract,
+        (admin, Vec::<crate::base::types::TokenAmount>::new(env)),
+    )
 }
 
 pub fn setup_supported_tokens(_env: &Env, _contract: &Address, _tokens: &Vec<Address>) {
@@ -104,12 +112,12 @@ pub fn create_test_members(env: &Env, count: u32) -> Vec<crate::base::types::Gro
         return members;
     }
 
-    let percentage_per_member = 100 / count;
+    let percentage_per_member = TOTAL_BASIS_POINTS / count;
     let mut total_percentage = 0;
 
     for i in 0..count {
         let percentage = if i == count - 1 {
-            100 - total_percentage
+            TOTAL_BASIS_POINTS - total_percentage
         } else {
             percentage_per_member
         };
@@ -118,11 +126,25 @@ pub fn create_test_members(env: &Env, count: u32) -> Vec<crate::base::types::Gro
         members.push_back(crate::base::types::GroupMember {
             address: Address::generate(env),
             percentage,
+            fixed_amount: 0,
+            fixed_token: None,
+            group: None,
         });
     }
     members
 }
 
+/// Returns a percentage-only member paid directly.
+pub fn member(address: &Address, percentage: u32) -> crate::base::types::GroupMember {
+    crate::base::types::GroupMember {
+        address: address.clone(),
+        percentage,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
+    }
+}
+
 pub fn create_test_group(
     env: &Env,
     contract: &Address,
@@ -143,7 +165,7 @@ pub fn create_test_group(
     let id = BytesN::from_array(env, &id_bytes);
     let name = String::from_str(env, "Test Group");
 
-    client.create(&id, &name, creator, &usages, token);
+    client.create(&id, &name, creator, &usages, token, &None);
 
     if !members.is_empty() {
         client.update_members(&id, creator, members);
@@ -152,6 +174,33 @@ pub fn create_test_group(
     id
 }
 
+/// Pays for and spends `usages` on a fresh group so the contract accrues
+/// `usages * fee` of withdrawable fee revenue in `token`. The token must be
+/// supported.
+pub fn accrue_fee_revenue(env: &Env, contract: &Address, token: &Address, usages: u32) {
+    let client = AutoShareContractClient::new(env, contract);
+    let payer = Address::generate(env);
+    let fee = client.get_usage_fee(token);
+    mint_tokens(env, token, &payer, (usages as i128) * fee);
+
+    let mut id_bytes = [0xffu8; 32];
+    id_bytes[0..4].copy_from_slice(&client.get_all_groups().len().to_be_bytes());
+    let id = BytesN::from_array(env, &id_bytes);
+    client.create(
+        &id,
+        &String::from_str(env, "Revenue"),
+        &payer,
+        &usages,
+        token,
+        &None,
+    );
+    env.as_contract(contract, || {
+        for _ in 0..usages {
+            crate::autoshare_logic::reduce_usage(env.clone(), id.clone()).unwrap();
+        }
+    });
+}
+
 pub fn fund_user_with_tokens(env: &Env, token: &Address, user: &Address, amount: i128) {
     mint_tokens(env, token, user, amount);
 }
@@ -45,7 +45,6 @@ fn test_autoshare_helpers() {
     let admin = Address::generate(&env);
     let contract = deploy_autoshare_contract(&env, &admin);
     let client = crate::AutoShareContractClient::new(&env, &contract);
-    client.initialize_admin(&admin);
 
     let token = deploy_mock_token(
         &env,
@@ -53,12 +52,16 @@ fn test_autoshare_helpers() {
         &String::from_str(&env, "TST"),
     );
     client.add_supported_token(&token, &admin);
+    client.set_usage_fee(&token, &10, &admin);
 
     let creator = Address::generate(&env);
     let mut members = Vec::new(&env);
     members.push_back(crate::base::types::GroupMember {
         address: Address::generate(&env),
-        percentage: 100,
+        percentage: 10000,
+        fixed_amount: 0,
+        fixed_token: None,
+        group: None,
     });
 
     let group_id = create_test_group(&env, &contract, &creator, &members, 1, &token);
@@ -0,0 +1,222 @@
+use super::test_utils::{accrue_fee_revenue, mint_tokens, setup_test_env};
+use crate::autoshare_logic::MAX_TIMELOCK_DELAY;
+use crate::base::errors::Error;
+use crate::base::types::{AdminAction, Role};
+use crate::mock_token::MockTokenClient;
+use crate::AutoShareContractClient;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Vec,
+};
+
+const DELAY: u64 = 3600;
+
+#[test]
+fn test_set_timelock_delay() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    assert_eq!(client.get_timelock_delay(), 0);
+    client.set_timelock_delay(&test_env.admin, &DELAY);
+    assert_eq!(client.get_timelock_delay(), DELAY);
+
+    // Once set, the delay can only change through the queue
+    assert_eq!(
+        client.try_set_timelock_delay(&test_env.admin, &0),
+        Err(Ok(Error::TimelockActive))
+    );
+}
+
+#[test]
+fn test_set_timelock_delay_bounds_and_auth() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+
+    assert_eq!(
+        client.try_set_timelock_delay(&test_env.admin, &(MAX_TIMELOCK_DELAY + 1)),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_timelock_delay(&Address::generate(env), &DELAY),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_sensitive_entrypoints_require_queue() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let admin = &test_env.admin;
+
+    mint_tokens(env, &token, &test_env.autoshare_contract, 1000);
+    client.set_timelock_delay(admin, &DELAY);
+
+    assert_eq!(
+        client.try_withdraw(admin, &token, &100, admin),
+        Err(Ok(Error::TimelockActive))
+    );
+    assert_eq!(
+        client.try_set_usage_fee(&token, &20, admin),
+        Err(Ok(Error::TimelockActive))
+    );
+    assert_eq!(
+        client.try_remove_supported_token(&token, admin),
+        Err(Ok(Error::TimelockActive))
+    );
+
+    // Actions outside the timelock still apply immediately
+    client.add_supported_token(&Address::generate(env), admin);
+    client.pause(admin);
+}
+
+#[test]
+fn test_queued_withdraw_runs_after_eta() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let contract = &test_env.autoshare_contract;
+    let client = AutoShareContractClient::new(env, contract);
+    let token = test_env.mock_tokens.get(0).unwrap().clone();
+    let recipient = Address::generate(env);
+
+    accrue_fee_revenue(env, contract, &token, 100);
+    env.ledger().set_timestamp(10_000);
+    client.set_timelock_delay(&test_env.admin, &DELAY);
+
+    let action = AdminAction::Withdraw(token.clone(), 300, recipient.clone());
+    let action_id = client.queue_action(&test_env.admin, &action);
+
+    let queued = client.get_queued_action(&action_id);
+    assert_eq!(queued.action, action);
+    assert_eq!(queued.eta, 10_000 + DELAY);
+    assert_eq!(queued.queued_by, test_env.admin);
+
+    env.ledger().set_timestamp(10_000 + DELAY - 1);
+    assert_eq!(
+        client.try_execute_queued_action(&action_id),
+        Err(Ok(Error::TimelockNotReady))
+    );
+    assert_eq!(client.get_contract_balance(&token), 1000);
+
+    env.ledger().set_timestamp(10_000 + DELAY);
+    client.execute_queued_action(&action_id);
+
+    assert_eq!(client.get_contract_balance(&token), 700);
+    assert_eq!(MockTokenClient::new(env, &token).balance(&recipient), 300);
+    assert_eq!(
+        client.try_execute_queued_action(&action_id),
+        Err(Ok(Error::NotFound))
+    );
+}
+
+#[test]
+fn test_admin_can_cancel_queued_action() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    client.set_timelock_delay(&test_env.admin, &DELAY);
+    let action_id = client.queue_action(
+        &test_env.admin,
+        &AdminAction::SetUsageFee(token.clone(), 50),
+    );
+
+    assert_eq!(
+        client.try_cancel_queued_action(&Address::generate(env), &action_id),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.cancel_queued_action(&test_env.admin, &action_id);
+
+    env.ledger().set_timestamp(DELAY + 1);
+    assert_eq!(
+        client.try_execute_queued_action(&action_id),
+        Err(Ok(Error::NotFound))
+    );
+    assert_eq!(client.get_usage_fee(&token), 10);
+}
+
+#[test]
+fn test_queue_action_checks_role() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let fee_manager = Address::generate(env);
+
+    client.set_timelock_delay(&test_env.admin, &DELAY);
+    client.grant_role(&test_env.admin, &Role::FeeManager, &fee_manager);
+
+    assert_eq!(
+        client.try_queue_action(
+            &Address::generate(env),
+            &AdminAction::SetUsageFee(token.clone(), 50)
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_queue_action(&fee_manager, &AdminAction::SetTimelockDelay(0)),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    let action_id = client.queue_action(&fee_manager, &AdminAction::SetUsageFee(token.clone(), 50));
+    env.ledger().set_timestamp(DELAY);
+    client.execute_queued_action(&action_id);
+    assert_eq!(client.get_usage_fee(&token), 50);
+}
+
+#[test]
+fn test_queue_rejects_actions_outside_timelock() {
+    let test_env = setup_test_env();
+    let client = AutoShareContractClient::new(&test_env.env, &test_env.autoshare_contract);
+
+    assert_eq!(
+        client.try_queue_action(&test_env.admin, &AdminAction::Pause),
+        Err(Ok(Error::InvalidInput))
+    );
+}
+
+#[test]
+fn test_delay_change_is_queued() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    client.set_timelock_delay(&test_env.admin, &DELAY);
+    let action_id = client.queue_action(&test_env.admin, &AdminAction::SetTimelockDelay(0));
+    assert_eq!(client.get_timelock_delay(), DELAY);
+
+    env.ledger().set_timestamp(DELAY);
+    client.execute_queued_action(&action_id);
+    assert_eq!(client.get_timelock_delay(), 0);
+    client.set_usage_fee(&token, &15, &test_env.admin);
+}
+
+#[test]
+fn test_council_approval_queues_timelocked_action() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+
+    client.set_timelock_delay(&test_env.admin, &DELAY);
+    let signer = Address::generate(env);
+    let mut signers = Vec::new(env);
+    signers.push_back(signer.clone());
+    client.set_council(&test_env.admin, &signers, &1);
+
+    let proposal_id = client.propose_action(&signer, &AdminAction::SetUsageFee(token.clone(), 70));
+    assert!(client.get_proposal(&proposal_id).executed);
+    assert_eq!(client.get_usage_fee(&token), 10);
+
+    let queued = client.get_queued_action(&0);
+    assert_eq!(queued.action, AdminAction::SetUsageFee(token.clone(), 70));
+    assert_eq!(queued.queued_by, test_env.autoshare_contract);
+
+    env.ledger().set_timestamp(DELAY);
+    client.execute_queued_action(&0);
+    assert_eq!(client.get_usage_fee(&token), 70);
+}
@@ -0,0 +1,295 @@
+use super::test_utils::{
+    assert_balance, create_test_group, member, mint_tokens, setup_test_env, TestEnv,
+};
+use crate::autoshare_logic::DataKey;
+use crate::base::errors::Error;
+use crate::base::types::{GroupMember, Tranche};
+use crate::AutoShareContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Vec};
+
+/// Creates a group whose first 1000 go entirely to `investor`, after which
+/// everything is split 50/50 between `investor` and `artist`.
+fn create_waterfall_group(
+    test_env: &TestEnv,
+    client: &AutoShareContractClient,
+    investor: &Address,
+    artist: &Address,
+    usages: u32,
+) -> BytesN<32> {
+    let env = &test_env.env;
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &vec![env, member(investor, 5000), member(artist, 5000)],
+        usages,
+        &token,
+    );
+    let tranches = vec![
+        env,
+        Tranche {
+            cap: Some(1000),
+            members: vec![env, member(investor, 10_000)],
+        },
+        Tranche {
+            cap: None,
+            members: vec![env, member(investor, 5000), member(artist, 5000)],
+        },
+    ];
+    client.set_tranches(&id, &creator, &tranches);
+    id
+}
+
+#[test]
+fn test_set_and_get_tranches() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap();
+    let id = create_waterfall_group(
+        &test_env,
+        &client,
+        &Address::generate(env),
+        &Address::generate(env),
+        1,
+    );
+
+    let tranches = client.get_tranches(&id);
+    assert_eq!(tranches.len(), 2);
+    assert_eq!(tranches.get(0).unwrap().cap, Some(1000));
+    assert_eq!(tranches.get(1).unwrap().cap, None);
+
+    // An empty list clears the waterfall
+    client.set_tranches(&id, &creator, &Vec::new(env));
+    assert_eq!(client.get_tranches(&id).len(), 0);
+    assert_eq!(
+        client.try_get_tranches(&BytesN::from_array(env, &[9u8; 32])),
+        Err(Ok(Error::NotFound))
+    );
+}
+
+#[test]
+fn test_set_tranches_is_creator_only() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let id = create_waterfall_group(
+        &test_env,
+        &client,
+        &Address::generate(env),
+        &Address::generate(env),
+        1,
+    );
+
+    assert_eq!(
+        client.try_set_tranches(&id, &Address::generate(env), &Vec::new(env)),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_set_tranches_validates_caps_and_members() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap();
+    let payee = Address::generate(env);
+    let id = create_waterfall_group(&test_env, &client, &payee, &Address::generate(env), 1);
+
+    let full = vec![env, member(&payee, 10_000)];
+    let capped = |cap: Option<i128>| Tranche {
+        cap,
+        members: full.clone(),
+    };
+
+    // The last tranche must be uncapped and only the last one
+    assert_eq!(
+        client.try_set_tranches(&id, &creator, &vec![env, capped(Some(100))]),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_tranches(&id, &creator, &vec![env, capped(None), capped(None)]),
+        Err(Ok(Error::InvalidInput))
+    );
+    // Caps must be positive and increasing
+    assert_eq!(
+        client.try_set_tranches(&id, &creator, &vec![env, capped(Some(0)), capped(None)]),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_tranches(
+            &id,
+            &creator,
+            &vec![env, capped(Some(500)), capped(Some(500)), capped(None)]
+        ),
+        Err(Ok(Error::InvalidInput))
+    );
+    // Each tranche's percentages must add up
+    let partial = Tranche {
+        cap: None,
+        members: vec![env, member(&payee, 5000)],
+    };
+    assert_eq!(
+        client.try_set_tranches(&id, &creator, &vec![env, partial]),
+        Err(Ok(Error::InvalidTotalPercentage))
+    );
+    // Tranche members take percentages only
+    let fixed = Tranche {
+        cap: None,
+        members: vec![
+            env,
+            member(&payee, 10_000),
+            GroupMember {
+                address: Address::generate(env),
+                percentage: 0,
+                fixed_amount: 10,
+                fixed_token: Some(test_env.mock_tokens.get(0).unwrap()),
+                group: None,
+            },
+        ],
+    };
+    assert_eq!(
+        client.try_set_tranches(&id, &creator, &vec![env, fixed]),
+        Err(Ok(Error::InvalidInput))
+    );
+}
+
+#[test]
+fn test_payment_crossing_cap_is_split_across_tranches() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let investor = Address::generate(env);
+    let artist = Address::generate(env);
+    let id = create_waterfall_group(&test_env, &client, &investor, &artist, 3);
+
+    mint_tokens(env, &token, &sender, 2000);
+    client.distribute(&id, &token, &600, &sender);
+    assert_balance(env, &token, &investor, 600);
+    assert_balance(env, &token, &artist, 0);
+
+    // 400 fills the first tranche, the other 600 is split 50/50
+    let preview = client.preview_distribution(&id, &token, &1000);
+    assert_eq!(preview.len(), 2);
+    assert_eq!(preview.get(0).unwrap().amount, 700);
+    assert_eq!(preview.get(1).unwrap().amount, 300);
+
+    client.distribute(&id, &token, &1000, &sender);
+    assert_balance(env, &token, &investor, 1300);
+    assert_balance(env, &token, &artist, 300);
+
+    client.distribute(&id, &token, &400, &sender);
+    assert_balance(env, &token, &investor, 1500);
+    assert_balance(env, &token, &artist, 500);
+
+    let history = client.get_group_distributions(&id);
+    assert_eq!(history.get(0).unwrap().tranches, vec![env, 0]);
+    assert_eq!(history.get(1).unwrap().tranches, vec![env, 0, 1]);
+    assert_eq!(history.get(2).unwrap().tranches, vec![env, 1]);
+    assert_eq!(history.get(1).unwrap().member_amounts.len(), 2);
+}
+
+#[test]
+fn test_cumulative_received_is_tracked_per_token() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let id = create_waterfall_group(
+        &test_env,
+        &client,
+        &Address::generate(env),
+        &Address::generate(env),
+        2,
+    );
+
+    assert_eq!(client.get_cumulative_received(&id, &token), 0);
+    mint_tokens(env, &token, &sender, 1500);
+    client.distribute(&id, &token, &700, &sender);
+    client.distribute(&id, &token, &800, &sender);
+
+    assert_eq!(client.get_cumulative_received(&id, &token), 1500);
+    assert_eq!(
+        client.get_cumulative_received(&id, &Address::generate(env)),
+        0
+    );
+}
+
+#[test]
+fn test_group_without_tranches_records_none() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let token = test_env.mock_tokens.get(0).unwrap();
+    let creator = test_env.users.get(0).unwrap();
+    let sender = test_env.users.get(1).unwrap();
+    let payee = Address::generate(env);
+    let id = create_test_group(
+        env,
+        &test_env.autoshare_contract,
+        &creator,
+        &vec![env, member(&payee, 10_000)],
+        1,
+        &token,
+    );
+
+    mint_tokens(env, &token, &sender, 500);
+    client.distribute(&id, &token, &500, &sender);
+
+    assert_balance(env, &token, &payee, 500);
+    let dist = client.get_group_distributions(&id).get(0).unwrap();
+    assert_eq!(dist.tranches.len(), 0);
+
+    // Nothing is tracked for caps the group does not have
+    assert_eq!(client.get_cumulative_received(&id, &token), 0);
+    env.as_contract(&test_env.autoshare_contract, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DataKey::GroupReceived(id.clone())));
+    });
+}
+
+#[test]
+fn test_tranche_members_are_indexed() {
+    let test_env = setup_test_env();
+    let env = &test_env.env;
+    let client = AutoShareContractClient::new(env, &test_env.autoshare_contract);
+    let creator = test_env.users.get(0).unwrap();
+    let investor = Address::generate(env);
+    let artist = Address::generate(env);
+    let id = create_waterfall_group(&test_env, &client, &investor, &artist, 1);
+
+    // An address paid only through a tranche is listed like a member
+    let angel = Address::generate(env);
+    let mut tranches = client.get_tranches(&id);
+    tranches.set(
+        1,
+        Tranche {
+            cap: None,
+            members: vec![env, member(&artist, 5000), member(&angel, 5000)],
+        },
+    );
+    client.set_tranches(&id, &creator, &tranches);
+    assert_eq!(client.get_groups_by_member(&angel).len(), 1);
+
+    // Dropping the investor from the members keeps them listed through a tranche
+    client.update_members(&id, &creator, &vec![env, member(&artist, 10_000)]);
+    assert_eq!(client.get_groups_by_member(&investor).len(), 1);
+
+    client.set_tranches(&id, &creator, &Vec::new(env));
+    assert_eq!(client.get_groups_by_member(&angel).len(), 0);
+    assert_eq!(client.get_groups_by_member(&investor).len(), 0);
+    assert_eq!(client.get_groups_by_member(&artist).len(), 1);
+
+    client.set_tranches(&id, &creator, &tranches);
+    client.deactivate_group(&id, &creator);
+    client.delete_group(&id, &creator);
+    assert_eq!(client.get_groups_by_member(&angel).len(), 0);
+    assert_eq!(client.get_groups_by_member(&artist).len(), 0);
+}